@@ -0,0 +1,171 @@
+//! Lowers a resolved `hrml::template::Node` tree into Rust statements that
+//! append to a `String` named `__hrml_out`, instead of interpreting the
+//! tree against a `Context` on every render. Only the subset of HRML with
+//! a direct, type-checked Rust equivalent is supported:
+//!
+//! - plain text
+//! - `<?get id="a.b"?>` - field access (`self.a.b`), HTML-escaped unless
+//!   `raw`; `<?get expr="..."?>` isn't supported (no dynamic context to
+//!   evaluate it against)
+//! - `<?if cond="a.b"?>...<?else?>...<?/if?>` - a bare field path (used as
+//!   a bool) or a `field OP literal` comparison (`==`, `!=`, `<`, `>`,
+//!   `<=`, `>=`)
+//! - `<?for in="item items"?>...<?/for?>` - iterates an array field;
+//!   `<?for in="key value map"?>` has no typed equivalent and isn't
+//!   supported
+//!
+//! `<?macro?>`/`<?call?>` and anything outside the above are reported as a
+//! compile error naming the unsupported construct, rather than silently
+//! producing an incomplete render - templates that need them should stay
+//! on `Engine::render`.
+use std::collections::HashMap;
+
+use hrml::template::Node;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Member;
+
+pub fn lower(nodes: &[Node]) -> Result<TokenStream, String> {
+    lower_scoped(nodes, &[])
+}
+
+/// `scope` holds the loop variables bound by enclosing `<?for?>`s (innermost
+/// last), so a path's first segment can be recognized as a loop item instead
+/// of always being read off `self`.
+fn lower_scoped(nodes: &[Node], scope: &[String]) -> Result<TokenStream, String> {
+    let mut out = TokenStream::new();
+    for node in nodes {
+        out.extend(lower_node(node, scope)?);
+    }
+    Ok(out)
+}
+
+fn lower_node(node: &Node, scope: &[String]) -> Result<TokenStream, String> {
+    match node {
+        Node::Text(text, _) => Ok(quote! { __hrml_out.push_str(#text); }),
+        Node::VoidElement { name, attrs, .. } if name == "get" => lower_get(attrs, scope),
+        Node::VoidElement { name, .. } => Err(format!(
+            "<?{}?> has no compile-time equivalent; keep this template on Engine::render",
+            name
+        )),
+        Node::Element { name, attrs, children, .. } => match name.as_str() {
+            "if" => lower_if(attrs, children, scope),
+            "for" => lower_for(attrs, children, scope),
+            "block" | "slot" => lower_scoped(children, scope),
+            _ => Err(format!(
+                "<?{}?> has no compile-time equivalent; keep this template on Engine::render",
+                name
+            )),
+        },
+    }
+}
+
+fn lower_get(attrs: &HashMap<String, String>, scope: &[String]) -> Result<TokenStream, String> {
+    let Some(id) = attrs.get("id") else {
+        return Err("<?get?> needs an `id` attribute to lower at compile time (`expr` isn't supported)".to_string());
+    };
+    let field = field_path(id, scope)?;
+    if attrs.contains_key("raw") {
+        Ok(quote! { __hrml_out.push_str(&::std::string::ToString::to_string(&#field)); })
+    } else {
+        Ok(quote! { __hrml_out.push_str(&::hrml::html::escape_html(&::std::string::ToString::to_string(&#field))); })
+    }
+}
+
+fn lower_if(attrs: &HashMap<String, String>, children: &[Node], scope: &[String]) -> Result<TokenStream, String> {
+    let cond = attrs.get("cond").cloned().unwrap_or_default();
+    let condition = lower_condition(&cond, scope)?;
+    let (true_nodes, false_nodes) = split_else(children);
+    let true_body = lower_scoped(&true_nodes, scope)?;
+    let false_body = lower_scoped(&false_nodes, scope)?;
+    Ok(quote! {
+        if #condition {
+            #true_body
+        } else {
+            #false_body
+        }
+    })
+}
+
+/// Mirrors `template.rs`'s own `split_if_children`: everything before a
+/// top-level `<?else?>` is the true branch, everything after is the false
+/// branch.
+fn split_else(children: &[Node]) -> (Vec<Node>, Vec<Node>) {
+    let else_pos = children
+        .iter()
+        .position(|n| matches!(n, Node::VoidElement { name, .. } if name == "else"));
+    match else_pos {
+        Some(pos) => (children[..pos].to_vec(), children[pos + 1..].to_vec()),
+        None => (children.to_vec(), Vec::new()),
+    }
+}
+
+fn lower_condition(cond: &str, scope: &[String]) -> Result<TokenStream, String> {
+    let tokens: Vec<&str> = cond.split_whitespace().collect();
+    match tokens.as_slice() {
+        [field] => {
+            let field = field_path(field, scope)?;
+            Ok(quote! { (#field) })
+        }
+        [field, op @ ("==" | "!=" | ">" | "<" | ">=" | "<="), rhs] => {
+            let field = field_path(field, scope)?;
+            let op: TokenStream = op.parse().unwrap();
+            let rhs: TokenStream = rhs
+                .parse()
+                .map_err(|_| format!("`{}` isn't a literal a compile-time <?if?> can compare against", rhs))?;
+            Ok(quote! { (#field #op #rhs) })
+        }
+        _ => Err(format!(
+            "<?if cond=\"{}\"?> isn't a supported compile-time condition (a field, or `field OP literal`)",
+            cond
+        )),
+    }
+}
+
+fn lower_for(attrs: &HashMap<String, String>, children: &[Node], scope: &[String]) -> Result<TokenStream, String> {
+    let spec = attrs.get("in").cloned().unwrap_or_default();
+    let tokens: Vec<&str> = spec.split_whitespace().collect();
+    let [item, path] = tokens.as_slice() else {
+        return Err(format!(
+            "<?for in=\"{}\"?>: compile-time <?for?> only supports `in=\"item path\"` over an array field",
+            spec
+        ));
+    };
+    let item_ident = syn::Ident::new(item, proc_macro2::Span::call_site());
+    let collection = field_path(path, scope)?;
+    let mut inner_scope = scope.to_vec();
+    inner_scope.push((*item).to_string());
+    let body = lower_scoped(children, &inner_scope)?;
+    Ok(quote! {
+        for #item_ident in #collection.iter() {
+            #body
+        }
+    })
+}
+
+/// `a.b.c` -> `self.a.b.c`, unless `a` is a bound `<?for?>` loop variable
+/// (innermost shadowing outer), in which case it's `a.b.c` off that item
+/// directly - each dotted segment past the base is still a plain Rust field
+/// access, so the deriving struct's shape has to match the template's
+/// paths exactly.
+fn field_path(path: &str, scope: &[String]) -> Result<TokenStream, String> {
+    if path.is_empty() || !path.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '.') {
+        return Err(format!("`{}` isn't a supported compile-time field path", path));
+    }
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap();
+
+    let mut expr = if scope.iter().any(|bound| bound == first) {
+        let item_ident = syn::Ident::new(first, proc_macro2::Span::call_site());
+        quote! { #item_ident }
+    } else {
+        let member: Member = syn::parse_str(first).map_err(|_| format!("`{}` isn't a valid field name", first))?;
+        quote! { self.#member }
+    };
+
+    for segment in segments {
+        let member: Member = syn::parse_str(segment).map_err(|_| format!("`{}` isn't a valid field name", segment))?;
+        expr = quote! { #expr.#member };
+    }
+    Ok(expr)
+}
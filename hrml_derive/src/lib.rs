@@ -0,0 +1,84 @@
+//! Compile-time companion to `hrml::template::Engine` (mirrors
+//! `askama_derive`'s split from `askama`): `#[derive(Template)]` reads the
+//! `#[template(path = "...")]` file, runs the same `Parser` and
+//! `load`/`block`/`slot` resolution the runtime `Engine` uses, and emits an
+//! `impl Display` that lowers `for`/`if`/`get` to real Rust control flow
+//! and field access against the deriving struct — no per-request parse and
+//! no `serde_json::Value` context. See `codegen` for exactly which HRML
+//! constructs have a compile-time equivalent; anything else is a
+//! compile error pointing back at the template, so authors know to keep
+//! that template on `Engine::render` instead.
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitStr};
+
+mod codegen;
+
+#[proc_macro_derive(Template, attributes(template))]
+pub fn derive_template(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let struct_name = &input.ident;
+
+    let template_path = match read_template_path(&input) {
+        Ok(path) => path,
+        Err(err) => return err.to_compile_error().into(),
+    };
+
+    // Templates live under `<crate root>/templates`, same convention the
+    // runtime `Engine` uses (`config.templates_path` defaults to the same
+    // directory name).
+    let base_path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates");
+    let engine = hrml::template::Engine::new(&base_path.to_string_lossy());
+
+    let nodes = match engine.resolve_for_tooling(&template_path) {
+        Ok(nodes) => nodes,
+        Err(diagnostic) => {
+            return syn::Error::new(struct_name.span(), diagnostic.message())
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let body = match codegen::lower(&nodes) {
+        Ok(body) => body,
+        Err(message) => return syn::Error::new(struct_name.span(), message).to_compile_error().into(),
+    };
+
+    let expanded = quote! {
+        impl ::std::fmt::Display for #struct_name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                let mut __hrml_out = ::std::string::String::new();
+                #body
+                f.write_str(&__hrml_out)
+            }
+        }
+
+        impl ::hrml::template::Template for #struct_name {}
+    };
+
+    expanded.into()
+}
+
+/// Reads the `path` key out of the struct's `#[template(path = "...")]`.
+fn read_template_path(input: &DeriveInput) -> syn::Result<String> {
+    for attr in &input.attrs {
+        if !attr.path().is_ident("template") {
+            continue;
+        }
+        let mut path = None;
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("path") {
+                let value: LitStr = meta.value()?.parse()?;
+                path = Some(value.value());
+            }
+            Ok(())
+        })?;
+        if let Some(path) = path {
+            return Ok(path);
+        }
+    }
+    Err(syn::Error::new_spanned(
+        &input.ident,
+        "#[derive(Template)] requires a #[template(path = \"...\")] attribute",
+    ))
+}
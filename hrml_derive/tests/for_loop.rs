@@ -0,0 +1,26 @@
+//! Regression test for `<?for?>` lowering: the loop item must be read off
+//! the bound loop variable (`post.title`), not off `self` (`self.post`,
+//! which doesn't exist on `PostsPage`).
+use hrml_derive::Template;
+
+struct Post {
+    title: String,
+}
+
+#[derive(Template)]
+#[template(path = "for_loop_test.hrml")]
+struct PostsPage {
+    posts: Vec<Post>,
+}
+
+#[test]
+fn for_loop_reads_item_field_not_self() {
+    let page = PostsPage {
+        posts: vec![
+            Post { title: "First".to_string() },
+            Post { title: "Second".to_string() },
+        ],
+    };
+
+    assert_eq!(page.to_string(), "<ul><li>First</li><li>Second</li></ul>");
+}
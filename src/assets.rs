@@ -0,0 +1,111 @@
+//! Fingerprints files under `static/` into content-hashed names
+//! (`css/style.css` -> `css/style.a1b2c3d4.css`) so `<?asset?>` URLs can be
+//! cached forever without a manual version bump. Built once at server
+//! start (and again during `build`) so the manifest - and therefore the
+//! URLs pages actually render - always matches what's on disk.
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize)]
+struct AssetEntry {
+    fingerprinted_path: String,
+    hash: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AssetManifest {
+    /// logical path ("css/style.css") -> entry
+    entries: HashMap<String, AssetEntry>,
+    /// fingerprinted path ("css/style.a1b2c3d4.css") -> logical path, for
+    /// serving a fingerprinted URL back to the real file on disk.
+    by_fingerprinted: HashMap<String, String>,
+}
+
+impl AssetManifest {
+    /// Walks `static_path` recursively, hashing every file's contents.
+    /// A missing `static_path` just yields an empty manifest - not every
+    /// project has static assets.
+    pub fn build(static_path: &Path) -> Result<Self, String> {
+        let mut manifest = Self::default();
+        if static_path.exists() {
+            manifest.walk(static_path, static_path)?;
+        }
+        Ok(manifest)
+    }
+
+    fn walk(&mut self, root: &Path, dir: &Path) -> Result<(), String> {
+        for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read '{}': {}", dir.display(), e))? {
+            let entry = entry.map_err(|e| e.to_string())?;
+            let path = entry.path();
+            if path.is_dir() {
+                self.walk(root, &path)?;
+                continue;
+            }
+
+            let bytes = fs::read(&path).map_err(|e| format!("Failed to read '{}': {}", path.display(), e))?;
+            let hash = hash_bytes(&bytes);
+            let logical = path
+                .strip_prefix(root)
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/");
+            let fingerprinted_path = fingerprint_name(&logical, &hash);
+
+            self.by_fingerprinted.insert(fingerprinted_path.clone(), logical.clone());
+            self.entries.insert(logical, AssetEntry { fingerprinted_path, hash });
+        }
+        Ok(())
+    }
+
+    /// What `<?asset path="..."?>` rewrites to. Falls back to the
+    /// unfingerprinted `/static/<path>` if `path` isn't in the manifest
+    /// (e.g. `static/` doesn't exist, or the file was added after the
+    /// manifest was built) rather than producing a broken link.
+    pub fn resolve(&self, logical_path: &str) -> String {
+        match self.entries.get(logical_path) {
+            Some(entry) => format!("/static/{}", entry.fingerprinted_path),
+            None => format!("/static/{}", logical_path),
+        }
+    }
+
+    /// Maps a requested `/static/*` path back to the real file path
+    /// relative to `static/`, and whether it was a fingerprinted request
+    /// (which decides the caching policy the caller should apply).
+    pub fn locate(&self, requested_path: &str) -> (String, bool) {
+        match self.by_fingerprinted.get(requested_path) {
+            Some(logical) => (logical.clone(), true),
+            None => (requested_path.to_string(), false),
+        }
+    }
+
+    /// The content hash already computed for `logical_path`, so unfingerprinted
+    /// requests can still get an `ETag` without re-hashing the file on every
+    /// request.
+    pub fn hash_of(&self, logical_path: &str) -> Option<&str> {
+        self.entries.get(logical_path).map(|entry| entry.hash.as_str())
+    }
+
+    /// All fingerprinted paths, relative to `static/` - used by `build` to
+    /// write each static file under its hashed name alongside the original.
+    pub fn fingerprinted_paths(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries
+            .iter()
+            .map(|(logical, entry)| (logical.as_str(), entry.fingerprinted_path.as_str()))
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())[..8].to_string()
+}
+
+fn fingerprint_name(logical_path: &str, hash: &str) -> String {
+    match logical_path.rsplit_once('.') {
+        Some((stem, ext)) => format!("{}.{}.{}", stem, hash, ext),
+        None => format!("{}.{}", logical_path, hash),
+    }
+}
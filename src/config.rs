@@ -17,7 +17,13 @@ pub struct Config {
     
     #[serde(default = "default_static_path")]
     pub static_path: String,
-    
+
+    #[serde(default = "default_migrations_path")]
+    pub migrations_path: String,
+
+    #[serde(default)]
+    pub database: DatabaseConfig,
+
     #[serde(default = "default_site_name")]
     pub site_name: String,
     
@@ -26,6 +32,72 @@ pub struct Config {
     
     #[serde(default)]
     pub favicon: Option<String>,
+
+    #[serde(default)]
+    pub build: BuildConfig,
+}
+
+/// `[build]` section consumed by `hrml build`: where the generated site
+/// goes, and (since not every page can render with an empty context) an
+/// optional explicit allow-list of routes to build instead of walking all
+/// of `templates/pages`.
+#[derive(Clone, Deserialize)]
+pub struct BuildConfig {
+    #[serde(default = "default_out_dir")]
+    pub out_dir: String,
+
+    #[serde(default)]
+    pub routes: Option<Vec<String>>,
+}
+
+fn default_out_dir() -> String {
+    "dist".to_string()
+}
+
+impl Default for BuildConfig {
+    fn default() -> Self {
+        Self {
+            out_dir: default_out_dir(),
+            routes: None,
+        }
+    }
+}
+
+/// `[database]` section: where the SQLite file lives, pool sizing/locking
+/// behavior, and whether the server is allowed to create the file (and
+/// apply migrations against it) on startup if it doesn't exist yet.
+#[derive(Clone, Deserialize)]
+pub struct DatabaseConfig {
+    #[serde(default = "default_database_path")]
+    pub path: String,
+
+    #[serde(default = "default_pool_size")]
+    pub pool_size: u32,
+
+    #[serde(default = "default_busy_timeout_ms")]
+    pub busy_timeout_ms: u32,
+
+    #[serde(default = "default_journal_mode")]
+    pub journal_mode: String,
+
+    #[serde(default = "default_auto_create")]
+    pub auto_create: bool,
+}
+
+fn default_auto_create() -> bool {
+    true
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            path: default_database_path(),
+            pool_size: default_pool_size(),
+            busy_timeout_ms: default_busy_timeout_ms(),
+            journal_mode: default_journal_mode(),
+            auto_create: default_auto_create(),
+        }
+    }
 }
 
 fn default_host() -> String {
@@ -48,6 +120,26 @@ fn default_static_path() -> String {
     "static".to_string()
 }
 
+fn default_migrations_path() -> String {
+    "migrations".to_string()
+}
+
+fn default_database_path() -> String {
+    "hrml.db".to_string()
+}
+
+fn default_pool_size() -> u32 {
+    8
+}
+
+fn default_busy_timeout_ms() -> u32 {
+    5_000
+}
+
+fn default_journal_mode() -> String {
+    "wal".to_string()
+}
+
 fn default_site_name() -> String {
     "HRML App".to_string()
 }
@@ -60,9 +152,12 @@ impl Default for Config {
             templates_path: default_templates_path(),
             endpoints_path: default_endpoints_path(),
             static_path: default_static_path(),
+            migrations_path: default_migrations_path(),
+            database: DatabaseConfig::default(),
             site_name: default_site_name(),
             site_description: None,
             favicon: None,
+            build: BuildConfig::default(),
         }
     }
 }
@@ -1,10 +1,20 @@
 use rusqlite::{Connection, Result as SqlResult, params};
 use serde_json::{json, Value};
-use std::sync::Mutex;
 use once_cell::sync::Lazy;
 
-static DB: Lazy<Mutex<Database>> = Lazy::new(|| {
-    Mutex::new(Database::new("hrml.db").expect("Failed to initialize database"))
+use crate::pool::{self, ConnectionOptions, JournalMode, Pool};
+
+static POOL: Lazy<Pool> = Lazy::new(|| {
+    pool::build(
+        "hrml.db",
+        8,
+        ConnectionOptions {
+            enable_foreign_keys: true,
+            busy_timeout_ms: 5_000,
+            journal_mode: JournalMode::Wal,
+        },
+    )
+    .expect("Failed to initialize database pool")
 });
 
 pub struct Database {
@@ -18,20 +28,33 @@ impl Database {
     }
 
     pub fn execute(&mut self, query: &str, params: Vec<Value>) -> SqlResult<usize> {
-        let mut stmt = self.conn.prepare(query)?;
+        Self::exec_on(&mut self.conn, query, params)
+    }
+
+    pub fn query(&self, query: &str, params: Vec<Value>) -> SqlResult<Vec<Value>> {
+        Self::query_on(&self.conn, query, params)
+    }
+
+    pub fn query_one(&self, query: &str, params: Vec<Value>) -> SqlResult<Value> {
+        let results = self.query(query, params)?;
+        Ok(results.into_iter().next().unwrap_or(Value::Null))
+    }
+
+    fn exec_on(conn: &mut Connection, query: &str, params: Vec<Value>) -> SqlResult<usize> {
+        let mut stmt = conn.prepare(query)?;
         let params: Vec<rusqlite::types::Value> = params.into_iter().map(json_to_sql).collect();
         stmt.execute(params.as_slice())
     }
 
-    pub fn query(&self, query: &str, params: Vec<Value>) -> SqlResult<Vec<Value>> {
-        let mut stmt = self.conn.prepare(query)?;
+    fn query_on(conn: &Connection, query: &str, params: Vec<Value>) -> SqlResult<Vec<Value>> {
+        let mut stmt = conn.prepare(query)?;
         let params: Vec<rusqlite::types::Value> = params.into_iter().map(json_to_sql).collect();
-        
+
         let column_count = stmt.column_count();
         let rows = stmt.query_map(params.as_slice(), |row| {
             let mut map = serde_json::Map::new();
             for i in 0..column_count {
-                let column_name = stmt.column_name(i).unwrap_or("");
+                let column_name = row.as_ref().column_name(i).unwrap_or("");
                 let value: rusqlite::types::Value = row.get(i)?;
                 map.insert(column_name.to_string(), sql_to_json(value));
             }
@@ -44,33 +67,23 @@ impl Database {
         }
         Ok(results)
     }
-
-    pub fn query_one(&self, query: &str, params: Vec<Value>) -> SqlResult<Value> {
-        let results = self.query(query, params)?;
-        Ok(results.into_iter().next().unwrap_or(Value::Null))
-    }
 }
 
-// Global database access
+// Global database access, backed by a pooled connection instead of a single
+// shared one.
 pub fn execute(query: &str, params: Vec<Value>) -> Result<usize, String> {
-    DB.lock()
-        .unwrap()
-        .execute(query, params)
-        .map_err(|e| e.to_string())
+    let mut conn = POOL.get().map_err(|e| e.to_string())?;
+    Database::exec_on(&mut conn, query, params).map_err(|e| e.to_string())
 }
 
 pub fn query(query: &str, params: Vec<Value>) -> Result<Vec<Value>, String> {
-    DB.lock()
-        .unwrap()
-        .query(query, params)
-        .map_err(|e| e.to_string())
+    let conn = POOL.get().map_err(|e| e.to_string())?;
+    Database::query_on(&conn, query, params).map_err(|e| e.to_string())
 }
 
 pub fn query_one(query: &str, params: Vec<Value>) -> Result<Value, String> {
-    DB.lock()
-        .unwrap()
-        .query_one(query, params)
-        .map_err(|e| e.to_string())
+    let results = self::query(query, params)?;
+    Ok(results.into_iter().next().unwrap_or(Value::Null))
 }
 
 // Conversion helpers
@@ -1,12 +1,83 @@
 use rusqlite::{Connection, Result as SqlResult};
+use serde::de::DeserializeOwned;
 use serde_json::{json, Value};
-use std::sync::Mutex;
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 
-static DB: Lazy<Mutex<Database>> = Lazy::new(|| {
-    Mutex::new(Database::new("hrml.db").expect("Failed to initialize database"))
+use crate::migrations;
+use crate::pool::{self, ConnectionOptions, JournalMode, Pool, PooledConnection};
+
+/// Settings the global pool is built from. Defaults match the hardcoded
+/// values this module used before `hrml.toml` gained a way to configure
+/// them; `init_settings` lets `run_server`/`check` override them from
+/// `Config` before the pool is first touched.
+struct DbSettings {
+    path: String,
+    pool_size: u32,
+    busy_timeout_ms: u32,
+    journal_mode: JournalMode,
+    migrations_path: String,
+}
+
+impl Default for DbSettings {
+    fn default() -> Self {
+        Self {
+            path: "hrml.db".to_string(),
+            pool_size: 8,
+            busy_timeout_ms: 5_000,
+            journal_mode: JournalMode::Wal,
+            migrations_path: "migrations".to_string(),
+        }
+    }
+}
+
+static SETTINGS: OnceCell<DbSettings> = OnceCell::new();
+
+/// Configures the global pool's connection settings. Must be called (if at
+/// all) before the first `db::` call in the process, since `POOL` only
+/// reads `SETTINGS` on first access; later calls are no-ops. Standalone
+/// callers (tests, `Table` usage without a `run_server`) that never call
+/// this keep getting the previous hardcoded defaults.
+pub fn init_settings(path: &str, pool_size: u32, busy_timeout_ms: u32, journal_mode: JournalMode, migrations_path: &str) {
+    let _ = SETTINGS.set(DbSettings {
+        path: path.to_string(),
+        pool_size,
+        busy_timeout_ms,
+        journal_mode,
+        migrations_path: migrations_path.to_string(),
+    });
+}
+
+static POOL: Lazy<Pool> = Lazy::new(|| {
+    let settings = SETTINGS.get_or_init(DbSettings::default);
+
+    {
+        // Run migrations once, up front, on a dedicated connection before
+        // the pool starts handing out connections to request handlers.
+        let mut conn = Connection::open(&settings.path)
+            .unwrap_or_else(|e| panic!("Failed to open {}: {}", settings.path, e));
+        migrations::apply_pending(&mut conn, &settings.migrations_path)
+            .expect("Failed to apply pending migrations");
+    }
+
+    pool::build(
+        &settings.path,
+        settings.pool_size,
+        ConnectionOptions {
+            enable_foreign_keys: true,
+            busy_timeout_ms: settings.busy_timeout_ms,
+            journal_mode: settings.journal_mode,
+        },
+    )
+    .expect("Failed to initialize database pool")
 });
 
+/// A handle to the shared pool, for callers (e.g. `AppState`) that want to
+/// hold their own reference rather than go through the `db::` free
+/// functions. Cheap to clone: `r2d2::Pool` is itself `Arc`-backed.
+pub fn shared_pool() -> Pool {
+    POOL.clone()
+}
+
 pub struct Database {
     conn: Connection,
 }
@@ -17,47 +88,157 @@ impl Database {
         Ok(Self { conn })
     }
 
+    /// Apply every pending migration found under `migrations_path` inside a
+    /// single transaction, tracked via the `_hrml_migrations` table.
+    pub fn migrate(&mut self, migrations_path: &str) -> Result<(), String> {
+        migrations::apply_pending(&mut self.conn, migrations_path)
+    }
+
     pub fn execute(&mut self, query: &str, params: Vec<Value>) -> SqlResult<usize> {
-        let params: Vec<Box<dyn rusqlite::ToSql>> = params.into_iter()
-            .map(|v| Box::new(json_to_sql_param(v)) as Box<dyn rusqlite::ToSql>)
-            .collect();
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
-            .map(|p| p.as_ref())
-            .collect();
-        self.conn.execute(query, params_refs.as_slice())
+        exec_on(&self.conn, query, params)
     }
 
     pub fn query(&self, query: &str, params: Vec<Value>) -> SqlResult<Vec<Value>> {
-        let mut stmt = self.conn.prepare(query)?;
-        let params: Vec<Box<dyn rusqlite::ToSql>> = params.into_iter()
-            .map(|v| Box::new(json_to_sql_param(v)) as Box<dyn rusqlite::ToSql>)
-            .collect();
-        let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
-            .map(|p| p.as_ref())
-            .collect();
-        
-        let column_count = stmt.column_count();
-        let rows = stmt.query_map(params_refs.as_slice(), |row| {
-            let mut map = serde_json::Map::new();
-            for i in 0..column_count {
-                let column_name = row.as_ref().column_name(i).unwrap_or("");
-                let value: rusqlite::types::Value = row.get(i)?;
-                map.insert(column_name.to_string(), sql_to_json(value));
-            }
-            Ok(Value::Object(map))
-        })?;
-
-        let mut results = Vec::new();
-        for row in rows {
-            results.push(row?);
-        }
-        Ok(results)
+        query_on(&self.conn, query, params)
     }
 
     pub fn query_one(&self, query: &str, params: Vec<Value>) -> SqlResult<Value> {
         let results = self.query(query, params)?;
         Ok(results.into_iter().next().unwrap_or(Value::Null))
     }
+
+    /// Like `query`, but deserializes each row directly into `T` instead of
+    /// leaving callers to pull fields back out of a `serde_json::Value`.
+    pub fn query_as<T: DeserializeOwned>(&self, query: &str, params: Vec<Value>) -> Result<Vec<T>, String> {
+        self.query(query, params)
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|row| serde_json::from_value(row).map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    pub fn query_one_as<T: DeserializeOwned>(&self, query: &str, params: Vec<Value>) -> Result<T, String> {
+        let row = self.query_one(query, params).map_err(|e| e.to_string())?;
+        serde_json::from_value(row).map_err(|e| e.to_string())
+    }
+}
+
+/// Extracts a row positionally instead of by column name, for the common
+/// "one or two scalar columns" case (`SELECT COUNT(*)`, `SELECT id, name`)
+/// where naming a struct would be overkill.
+pub trait FromRow: Sized {
+    fn from_row(row: &rusqlite::Row) -> SqlResult<Self>;
+}
+
+impl<A: rusqlite::types::FromSql> FromRow for (A,) {
+    fn from_row(row: &rusqlite::Row) -> SqlResult<Self> {
+        Ok((row.get(0)?,))
+    }
+}
+
+impl<A: rusqlite::types::FromSql, B: rusqlite::types::FromSql> FromRow for (A, B) {
+    fn from_row(row: &rusqlite::Row) -> SqlResult<Self> {
+        Ok((row.get(0)?, row.get(1)?))
+    }
+}
+
+impl<A: rusqlite::types::FromSql, B: rusqlite::types::FromSql, C: rusqlite::types::FromSql> FromRow for (A, B, C) {
+    fn from_row(row: &rusqlite::Row) -> SqlResult<Self> {
+        Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+    }
+}
+
+/// A single-connection transaction handle. Borrowed from the pool for its
+/// whole lifetime so every statement run through it (including
+/// `last_insert_rowid`) sees the same SQLite connection. Dropping the
+/// handle without calling `commit` (e.g. because the closure in
+/// `transaction` returned `Err`) rolls back, matching `BEGIN`/`ROLLBACK`.
+pub struct Transaction<'conn> {
+    inner: rusqlite::Transaction<'conn>,
+}
+
+impl<'conn> Transaction<'conn> {
+    pub fn execute(&self, query: &str, params: Vec<Value>) -> Result<usize, String> {
+        exec_on(&self.inner, query, params).map_err(|e| e.to_string())
+    }
+
+    pub fn query(&self, query: &str, params: Vec<Value>) -> Result<Vec<Value>, String> {
+        query_on(&self.inner, query, params).map_err(|e| e.to_string())
+    }
+
+    pub fn query_one(&self, query: &str, params: Vec<Value>) -> Result<Value, String> {
+        let results = self.query(query, params)?;
+        Ok(results.into_iter().next().unwrap_or(Value::Null))
+    }
+
+    pub fn last_insert_rowid(&self) -> i64 {
+        self.inner.last_insert_rowid()
+    }
+}
+
+/// Run `f` inside a SQLite transaction on a single connection checked out
+/// from the pool, committing if `f` returns `Ok` and rolling back otherwise.
+/// Lets callers group multiple `Table` operations atomically:
+/// `db::transaction(|tx| { tx.execute(..)?; tx.execute(..)?; Ok(()) })`.
+pub fn transaction<F, T>(f: F) -> Result<T, String>
+where
+    F: FnOnce(&Transaction) -> Result<T, String>,
+{
+    let mut conn = POOL.get().map_err(|e| e.to_string())?;
+    let inner = conn.transaction().map_err(|e| e.to_string())?;
+    let tx = Transaction { inner };
+
+    let result = f(&tx)?;
+    tx.inner.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// A transaction that, unlike `Transaction`/`transaction()`, stays open
+/// across separate calls instead of living inside one closure - what
+/// `db.transaction()`'s `__enter__`/`__exit__` in Python need, since those
+/// are two distinct calls into Rust with handler code running in between.
+/// Issues `BEGIN` immediately and rolls back on `Drop` if neither `commit`
+/// nor `rollback` was called, so a handler that raises mid-transaction
+/// doesn't leave one dangling.
+pub struct ActiveTransaction {
+    conn: PooledConnection,
+    finished: bool,
+}
+
+impl ActiveTransaction {
+    pub fn begin() -> Result<Self, String> {
+        let conn = POOL.get().map_err(|e| e.to_string())?;
+        conn.execute("BEGIN", []).map_err(|e| e.to_string())?;
+        Ok(Self { conn, finished: false })
+    }
+
+    pub fn execute(&self, query: &str, params: Vec<Value>) -> Result<usize, String> {
+        exec_on(&self.conn, query, params).map_err(|e| e.to_string())
+    }
+
+    pub fn query(&self, query: &str, params: Vec<Value>) -> Result<Vec<Value>, String> {
+        query_on(&self.conn, query, params).map_err(|e| e.to_string())
+    }
+
+    pub fn commit(&mut self) -> Result<(), String> {
+        self.conn.execute("COMMIT", []).map_err(|e| e.to_string())?;
+        self.finished = true;
+        Ok(())
+    }
+
+    pub fn rollback(&mut self) -> Result<(), String> {
+        self.conn.execute("ROLLBACK", []).map_err(|e| e.to_string())?;
+        self.finished = true;
+        Ok(())
+    }
+}
+
+impl Drop for ActiveTransaction {
+    fn drop(&mut self) {
+        if !self.finished {
+            let _ = self.conn.execute("ROLLBACK", []);
+        }
+    }
 }
 
 // High-level database operations
@@ -91,11 +272,12 @@ impl Table {
                 placeholders.join(", ")
             );
 
-            execute(&query, values)?;
-            
-            // Get last insert rowid
-            let result = query_one("SELECT last_insert_rowid() as id", vec![])?;
-            Ok(result.get("id").and_then(|v| v.as_i64()).unwrap_or(0))
+            // Run the insert and the rowid read on the same connection so
+            // the id can't belong to a different connection's insert.
+            transaction(|tx| {
+                tx.execute(&query, values)?;
+                Ok(tx.last_insert_rowid())
+            })
         } else {
             Err("Data must be an object".to_string())
         }
@@ -113,6 +295,12 @@ impl Table {
 
     pub fn find_where(&self, conditions: Value) -> Result<Vec<Value>, String> {
         if let Value::Object(map) = conditions {
+            for key in map.keys() {
+                if !is_valid_identifier(key) {
+                    return Err(format!("Invalid column name: {}", key));
+                }
+            }
+
             let where_clause: Vec<String> = map.keys()
                 .map(|k| format!("{} = ?", k))
                 .collect();
@@ -130,6 +318,40 @@ impl Table {
         }
     }
 
+    /// Start a chainable query against this table, e.g.
+    /// `table("posts").where_eq("published", json!(true)).order_by("created_at", Direction::Desc).limit(20).all()`.
+    pub fn query(&self) -> Query {
+        Query::new(&self.name)
+    }
+
+    pub fn where_eq(&self, column: &str, value: Value) -> Query {
+        self.query().where_eq(column, value)
+    }
+
+    pub fn where_op(&self, column: &str, op: Op, value: Value) -> Query {
+        self.query().where_op(column, op, value)
+    }
+
+    pub fn order_by(&self, column: &str, direction: Direction) -> Query {
+        self.query().order_by(column, direction)
+    }
+
+    pub fn limit(&self, n: i64) -> Query {
+        self.query().limit(n)
+    }
+
+    pub fn offset(&self, n: i64) -> Query {
+        self.query().offset(n)
+    }
+
+    pub fn select(&self, columns: &[&str]) -> Query {
+        self.query().select(columns)
+    }
+
+    pub fn join(&self, other_table: &str, on: &str) -> Query {
+        self.query().join(other_table, on)
+    }
+
     pub fn update(&self, id: i64, data: Value) -> Result<usize, String> {
         if let Value::Object(map) = data {
             let set_clause: Vec<String> = map.keys()
@@ -144,7 +366,7 @@ impl Table {
                 set_clause.join(", ")
             );
 
-            execute(&query, values)
+            transaction(|tx| tx.execute(&query, values))
         } else {
             Err("Data must be an object".to_string())
         }
@@ -152,30 +374,397 @@ impl Table {
 
     pub fn delete(&self, id: i64) -> Result<usize, String> {
         let query = format!("DELETE FROM {} WHERE id = ?", self.name);
-        execute(&query, vec![json!(id)])
+        transaction(|tx| tx.execute(&query, vec![json!(id)]))
+    }
+
+    /// Inserts every row in one transaction, returning their new ids in the
+    /// same order - for batch writes (`db.insert_many`) that should either
+    /// all land or none do.
+    pub fn insert_many(&self, rows: Vec<Value>) -> Result<Vec<i64>, String> {
+        transaction(|tx| {
+            let mut ids = Vec::with_capacity(rows.len());
+            for row in &rows {
+                let Value::Object(map) = row else {
+                    return Err("Each row must be an object".to_string());
+                };
+                let columns: Vec<String> = map.keys().cloned().collect();
+                let placeholders: Vec<String> = (0..columns.len()).map(|_| "?".to_string()).collect();
+                let values: Vec<Value> = map.values().cloned().collect();
+
+                let query = format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    self.name,
+                    columns.join(", "),
+                    placeholders.join(", ")
+                );
+                tx.execute(&query, values)?;
+                ids.push(tx.last_insert_rowid());
+            }
+            Ok(ids)
+        })
+    }
+
+    /// Builds a query from `[{"field": ..., "op": "eq"|"neq"|"gt"|"lt"|"gte"|"lte"|"like"|"in", "value": ...}, ...]`,
+    /// the predicate shape `db.where`/`db.count` accept from Python.
+    pub fn filtered(&self, filters: &Value) -> Result<Query, String> {
+        let Value::Array(items) = filters else {
+            return Err("Filters must be a JSON array of {field, op, value} objects".to_string());
+        };
+
+        let mut q = self.query();
+        for item in items {
+            let field = item
+                .get("field")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Each filter needs a string 'field'".to_string())?;
+            let op = match item.get("op").and_then(|v| v.as_str()).unwrap_or("eq") {
+                "eq" => Op::Eq,
+                "neq" => Op::Ne,
+                "gt" => Op::Gt,
+                "lt" => Op::Lt,
+                "gte" => Op::Gte,
+                "lte" => Op::Lte,
+                "like" => Op::Like,
+                "in" => Op::In,
+                other => return Err(format!("Unknown filter op: {}", other)),
+            };
+            let value = item.get("value").cloned().unwrap_or(Value::Null);
+            q = q.where_op(field, op, value);
+        }
+        Ok(q)
+    }
+}
+
+/// Table and column names interpolate directly into the generated SQL (the
+/// values never do — those stay bound `?` parameters), so every identifier
+/// accepted by `Query` is whitelisted against this before use.
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().next().map(|c| c.is_ascii_alphabetic() || c == '_').unwrap_or(false)
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Gte,
+    Lte,
+    Like,
+    In,
+}
+
+impl Op {
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Eq => "=",
+            Op::Ne => "!=",
+            Op::Gt => ">",
+            Op::Lt => "<",
+            Op::Gte => ">=",
+            Op::Lte => "<=",
+            Op::Like => "LIKE",
+            Op::In => "IN",
+        }
     }
 }
 
-// Global database access
+#[derive(Debug, Clone, Copy)]
+pub enum Direction {
+    Asc,
+    Desc,
+}
+
+impl Direction {
+    fn sql(self) -> &'static str {
+        match self {
+            Direction::Asc => "ASC",
+            Direction::Desc => "DESC",
+        }
+    }
+}
+
+/// Chainable query builder that accumulates clauses and emits one
+/// parameterized statement. Identifiers (table/column/direction) are
+/// whitelisted since they interpolate into the SQL string; values always
+/// flow through bound `?` parameters.
+pub struct Query {
+    table: String,
+    columns: Vec<String>,
+    joins: Vec<String>,
+    conditions: Vec<(String, Op, Value)>,
+    order: Option<(String, Direction)>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    error: Option<String>,
+}
+
+impl Query {
+    fn new(table: &str) -> Self {
+        Self {
+            table: table.to_string(),
+            columns: vec!["*".to_string()],
+            joins: Vec::new(),
+            conditions: Vec::new(),
+            order: None,
+            limit: None,
+            offset: None,
+            error: if is_valid_identifier(table) {
+                None
+            } else {
+                Some(format!("Invalid table name: {}", table))
+            },
+        }
+    }
+
+    fn invalid(&mut self, what: &str, name: &str) {
+        if self.error.is_none() {
+            self.error = Some(format!("Invalid {}: {}", what, name));
+        }
+    }
+
+    pub fn select(mut self, columns: &[&str]) -> Self {
+        if columns.iter().all(|c| is_valid_identifier(c)) {
+            self.columns = columns.iter().map(|c| c.to_string()).collect();
+        } else {
+            self.invalid("column name", &columns.join(", "));
+        }
+        self
+    }
+
+    pub fn where_eq(self, column: &str, value: Value) -> Self {
+        self.where_op(column, Op::Eq, value)
+    }
+
+    pub fn where_op(mut self, column: &str, op: Op, value: Value) -> Self {
+        if is_valid_identifier(column) {
+            self.conditions.push((column.to_string(), op, value));
+        } else {
+            self.invalid("column name", column);
+        }
+        self
+    }
+
+    /// `on` must be `lhs <op> rhs` (e.g. `"posts.author_id = users.id"`) -
+    /// both identifiers are whitelisted the same as every other interpolated
+    /// name here, rather than splicing the caller's string straight into the
+    /// query.
+    pub fn join(mut self, other_table: &str, on: &str) -> Self {
+        if !is_valid_identifier(other_table) {
+            self.invalid("table name", other_table);
+            return self;
+        }
+        match Self::parse_join_condition(on) {
+            Some((lhs, op, rhs)) => {
+                self.joins.push(format!("JOIN {} ON {} {} {}", other_table, lhs, op, rhs));
+            }
+            None => self.invalid("join condition", on),
+        }
+        self
+    }
+
+    fn parse_join_condition(on: &str) -> Option<(&str, &str, &str)> {
+        let tokens: Vec<&str> = on.split_whitespace().collect();
+        let [lhs, op, rhs] = tokens[..] else { return None };
+        let valid_op = matches!(op, "=" | "!=" | ">" | "<" | ">=" | "<=");
+        if valid_op && is_valid_identifier(lhs) && is_valid_identifier(rhs) {
+            Some((lhs, op, rhs))
+        } else {
+            None
+        }
+    }
+
+    pub fn order_by(mut self, column: &str, direction: Direction) -> Self {
+        if is_valid_identifier(column) {
+            self.order = Some((column.to_string(), direction));
+        } else {
+            self.invalid("column name", column);
+        }
+        self
+    }
+
+    pub fn limit(mut self, n: i64) -> Self {
+        self.limit = Some(n);
+        self
+    }
+
+    pub fn offset(mut self, n: i64) -> Self {
+        self.offset = Some(n);
+        self
+    }
+
+    /// Renders `conditions` into a `WHERE ...` clause (empty string if
+    /// there are none) and the params it binds against, shared by `all` and
+    /// `count` so they stay in sync on how each `Op` is rendered.
+    fn where_clause(conditions: Vec<(String, Op, Value)>) -> (String, Vec<Value>) {
+        if conditions.is_empty() {
+            return (String::new(), Vec::new());
+        }
+
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        for (column, op, value) in conditions {
+            match (op, &value) {
+                (Op::In, Value::Array(items)) => {
+                    let placeholders = vec!["?"; items.len()].join(", ");
+                    clauses.push(format!("{} IN ({})", column, placeholders));
+                    params.extend(items.iter().cloned());
+                }
+                _ => {
+                    clauses.push(format!("{} {} ?", column, op.sql()));
+                    params.push(value);
+                }
+            }
+        }
+        (format!(" WHERE {}", clauses.join(" AND ")), params)
+    }
+
+    /// Build and run the accumulated query, returning every matching row.
+    pub fn all(self) -> Result<Vec<Value>, String> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let mut sql = format!("SELECT {} FROM {}", self.columns.join(", "), self.table);
+        for join in &self.joins {
+            sql.push(' ');
+            sql.push_str(join);
+        }
+
+        let (where_clause, params) = Self::where_clause(self.conditions);
+        sql.push_str(&where_clause);
+
+        if let Some((column, direction)) = &self.order {
+            sql.push_str(&format!(" ORDER BY {} {}", column, direction.sql()));
+        }
+        if let Some(n) = self.limit {
+            sql.push_str(&format!(" LIMIT {}", n));
+        }
+        if let Some(n) = self.offset {
+            sql.push_str(&format!(" OFFSET {}", n));
+        }
+
+        query(&sql, params)
+    }
+
+    /// Same predicate as `all`, but returns a row count instead of the rows
+    /// themselves - ignores `select`/`order_by`/`limit`/`offset` since none
+    /// of those change how many rows match.
+    pub fn count(self) -> Result<i64, String> {
+        if let Some(e) = self.error {
+            return Err(e);
+        }
+
+        let mut sql = format!("SELECT COUNT(*) AS count FROM {}", self.table);
+        for join in &self.joins {
+            sql.push(' ');
+            sql.push_str(join);
+        }
+
+        let (where_clause, params) = Self::where_clause(self.conditions);
+        sql.push_str(&where_clause);
+
+        let row = query_one(&sql, params)?;
+        row.get("count")
+            .and_then(|v| v.as_i64())
+            .ok_or_else(|| "Failed to read row count".to_string())
+    }
+
+    /// Like `all`, but returns only the first matching row (or `Value::Null`).
+    pub fn one(self) -> Result<Value, String> {
+        let results = self.limit(1).all()?;
+        Ok(results.into_iter().next().unwrap_or(Value::Null))
+    }
+}
+
+// Global database access. Each call checks out a connection from the pool
+// rather than locking a single shared connection, so concurrent reads no
+// longer serialize behind each other (WAL mode lets them run alongside a
+// writer too).
 pub fn execute(query: &str, params: Vec<Value>) -> Result<usize, String> {
-    DB.lock()
-        .unwrap()
-        .execute(query, params)
-        .map_err(|e| e.to_string())
+    let conn = POOL.get().map_err(|e| e.to_string())?;
+    exec_on(&conn, query, params).map_err(|e| e.to_string())
 }
 
 pub fn query(query: &str, params: Vec<Value>) -> Result<Vec<Value>, String> {
-    DB.lock()
-        .unwrap()
-        .query(query, params)
-        .map_err(|e| e.to_string())
+    let conn = POOL.get().map_err(|e| e.to_string())?;
+    query_on(&conn, query, params).map_err(|e| e.to_string())
 }
 
 pub fn query_one(query: &str, params: Vec<Value>) -> Result<Value, String> {
-    DB.lock()
-        .unwrap()
-        .query_one(query, params)
-        .map_err(|e| e.to_string())
+    let results = self::query(query, params)?;
+    Ok(results.into_iter().next().unwrap_or(Value::Null))
+}
+
+/// `let users: Vec<User> = db::query_as("SELECT * FROM users", vec![])?;`
+pub fn query_as<T: DeserializeOwned>(query: &str, params: Vec<Value>) -> Result<Vec<T>, String> {
+    self::query(query, params)?
+        .into_iter()
+        .map(|row| serde_json::from_value(row).map_err(|e| e.to_string()))
+        .collect()
+}
+
+pub fn query_one_as<T: DeserializeOwned>(query: &str, params: Vec<Value>) -> Result<T, String> {
+    let row = self::query_one(query, params)?;
+    serde_json::from_value(row).map_err(|e| e.to_string())
+}
+
+/// Positional extraction into `(A,)`, `(A, B)`, … tuples via `FromRow`,
+/// bypassing the `serde_json::Value` round trip entirely.
+pub fn query_tuples<T: FromRow>(query: &str, params: Vec<Value>) -> Result<Vec<T>, String> {
+    let conn = POOL.get().map_err(|e| e.to_string())?;
+    let mut stmt = conn.prepare(query).map_err(|e| e.to_string())?;
+    let params: Vec<Box<dyn rusqlite::ToSql>> = params.into_iter()
+        .map(|v| Box::new(json_to_sql_param(v)) as Box<dyn rusqlite::ToSql>)
+        .collect();
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
+        .map(|p| p.as_ref())
+        .collect();
+
+    let rows = stmt
+        .query_map(params_refs.as_slice(), |row| T::from_row(row))
+        .map_err(|e| e.to_string())?;
+
+    rows.collect::<SqlResult<Vec<T>>>().map_err(|e| e.to_string())
+}
+
+fn exec_on(conn: &Connection, query: &str, params: Vec<Value>) -> SqlResult<usize> {
+    let params: Vec<Box<dyn rusqlite::ToSql>> = params.into_iter()
+        .map(|v| Box::new(json_to_sql_param(v)) as Box<dyn rusqlite::ToSql>)
+        .collect();
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
+        .map(|p| p.as_ref())
+        .collect();
+    conn.execute(query, params_refs.as_slice())
+}
+
+fn query_on(conn: &Connection, query: &str, params: Vec<Value>) -> SqlResult<Vec<Value>> {
+    let mut stmt = conn.prepare(query)?;
+    let params: Vec<Box<dyn rusqlite::ToSql>> = params.into_iter()
+        .map(|v| Box::new(json_to_sql_param(v)) as Box<dyn rusqlite::ToSql>)
+        .collect();
+    let params_refs: Vec<&dyn rusqlite::ToSql> = params.iter()
+        .map(|p| p.as_ref())
+        .collect();
+
+    let column_count = stmt.column_count();
+    let rows = stmt.query_map(params_refs.as_slice(), |row| {
+        let mut map = serde_json::Map::new();
+        for i in 0..column_count {
+            let column_name = row.as_ref().column_name(i).unwrap_or("");
+            let value: rusqlite::types::Value = row.get(i)?;
+            map.insert(column_name.to_string(), sql_to_json(value));
+        }
+        Ok(Value::Object(map))
+    })?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row?);
+    }
+    Ok(results)
 }
 
 pub fn table(name: &str) -> Table {
@@ -183,12 +772,19 @@ pub fn table(name: &str) -> Table {
 }
 
 // Conversion helpers
+
+/// Key used to tag a JSON object as a base64-encoded blob, e.g.
+/// `{"$blob": "aGVsbG8="}`, so blob columns round-trip through the
+/// JSON-valued API instead of becoming a stringified byte array.
+const BLOB_TAG_KEY: &str = "$blob";
+
 #[derive(Debug, Clone)]
 enum SqlParam {
     Null,
     Integer(i64),
     Real(f64),
     Text(String),
+    Blob(Vec<u8>),
 }
 
 impl rusqlite::ToSql for SqlParam {
@@ -198,10 +794,56 @@ impl rusqlite::ToSql for SqlParam {
             SqlParam::Integer(i) => Ok(rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Integer(*i))),
             SqlParam::Real(f) => Ok(rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Real(*f))),
             SqlParam::Text(s) => Ok(rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Text(s.clone()))),
+            SqlParam::Blob(b) => Ok(rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Blob(b.clone()))),
         }
     }
 }
 
+/// A pre-sized blob, written as zeros, that callers can later fill in place
+/// via `write_blob` — the JSON-valued equivalent of rusqlite's incremental
+/// blob I/O, for columns too large to build as one `Vec<u8>` up front.
+pub struct ZeroBlob(pub usize);
+
+impl rusqlite::ToSql for ZeroBlob {
+    fn to_sql(&self) -> rusqlite::Result<rusqlite::types::ToSqlOutput<'_>> {
+        Ok(rusqlite::types::ToSqlOutput::Owned(rusqlite::types::Value::Blob(
+            vec![0u8; self.0],
+        )))
+    }
+}
+
+/// Stream `data` into an already-allocated blob column (see `ZeroBlob`)
+/// using SQLite's incremental blob I/O, instead of re-sending the whole
+/// value through an `UPDATE`.
+pub fn write_blob(table: &str, column: &str, rowid: i64, data: &[u8]) -> Result<(), String> {
+    let conn = POOL.get().map_err(|e| e.to_string())?;
+    let mut blob = conn
+        .blob_open(rusqlite::DatabaseName::Main, table, column, rowid, false)
+        .map_err(|e| e.to_string())?;
+    std::io::Write::write_all(&mut blob, data).map_err(|e| e.to_string())
+}
+
+fn decode_blob_object(map: &serde_json::Map<String, Value>) -> Option<Vec<u8>> {
+    if map.len() != 1 {
+        return None;
+    }
+    map.get(BLOB_TAG_KEY)
+        .and_then(|v| v.as_str())
+        .and_then(|encoded| {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD.decode(encoded).ok()
+        })
+}
+
+fn array_as_byte_buffer(arr: &[Value]) -> Option<Vec<u8>> {
+    if arr.is_empty() {
+        return None;
+    }
+    arr.iter()
+        .map(|v| v.as_u64().filter(|n| *n <= u8::MAX as u64).map(|n| n as u8))
+        .collect()
+}
+
 fn json_to_sql_param(value: Value) -> SqlParam {
     match value {
         Value::Null => SqlParam::Null,
@@ -216,6 +858,14 @@ fn json_to_sql_param(value: Value) -> SqlParam {
             }
         }
         Value::String(s) => SqlParam::Text(s),
+        Value::Object(ref map) => match decode_blob_object(map) {
+            Some(bytes) => SqlParam::Blob(bytes),
+            None => SqlParam::Text(value.to_string()),
+        },
+        Value::Array(ref arr) => match array_as_byte_buffer(arr) {
+            Some(bytes) => SqlParam::Blob(bytes),
+            None => SqlParam::Text(value.to_string()),
+        },
         _ => SqlParam::Text(value.to_string()),
     }
 }
@@ -226,6 +876,9 @@ fn sql_to_json(value: rusqlite::types::Value) -> Value {
         rusqlite::types::Value::Integer(i) => json!(i),
         rusqlite::types::Value::Real(f) => json!(f),
         rusqlite::types::Value::Text(s) => json!(s),
-        rusqlite::types::Value::Blob(b) => json!(b),
+        rusqlite::types::Value::Blob(b) => {
+            use base64::Engine;
+            json!({ BLOB_TAG_KEY: base64::engine::general_purpose::STANDARD.encode(&b) })
+        }
     }
 }
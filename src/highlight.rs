@@ -0,0 +1,230 @@
+/// Server-side syntax highlighting: a small per-language lexer that emits
+/// `(TokenKind, byte_range)` spans over source text, rendered as `<span>`s
+/// with CSS classes (`kw`, `string`, `comment`, `number`, `ident`, `op`)
+/// inside a `<pre><code>` block — so highlighted docs ship with a stylesheet
+/// instead of a client-side highlighter bundle.
+use std::ops::Range;
+
+use crate::html::{code, pre, span, Element};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TokenKind {
+    Keyword,
+    String,
+    Comment,
+    Number,
+    Ident,
+    Op,
+    Plain,
+}
+
+impl TokenKind {
+    fn class(self) -> Option<&'static str> {
+        match self {
+            TokenKind::Keyword => Some("kw"),
+            TokenKind::String => Some("string"),
+            TokenKind::Comment => Some("comment"),
+            TokenKind::Number => Some("number"),
+            TokenKind::Ident => Some("ident"),
+            TokenKind::Op => Some("op"),
+            TokenKind::Plain => None,
+        }
+    }
+}
+
+struct LanguageConfig {
+    keywords: &'static [&'static str],
+    line_comment: Option<&'static str>,
+    block_comment: Option<(&'static str, &'static str)>,
+}
+
+fn language_config(language: &str) -> Option<LanguageConfig> {
+    match language.to_ascii_lowercase().as_str() {
+        "rust" | "rs" => Some(LanguageConfig {
+            keywords: &[
+                "fn", "let", "mut", "pub", "struct", "enum", "impl", "trait", "if", "else",
+                "match", "for", "while", "loop", "return", "use", "mod", "crate", "self", "Self",
+                "async", "await", "move", "ref", "static", "const", "dyn", "where", "break",
+                "continue", "true", "false", "in", "as", "unsafe", "type", "extern",
+            ],
+            line_comment: Some("//"),
+            block_comment: Some(("/*", "*/")),
+        }),
+        "python" | "py" => Some(LanguageConfig {
+            keywords: &[
+                "def", "class", "import", "from", "as", "if", "elif", "else", "for", "while",
+                "return", "yield", "with", "try", "except", "finally", "raise", "pass", "break",
+                "continue", "lambda", "in", "is", "not", "and", "or", "None", "True", "False",
+                "global", "nonlocal", "async", "await",
+            ],
+            line_comment: Some("#"),
+            block_comment: None,
+        }),
+        "javascript" | "js" | "typescript" | "ts" => Some(LanguageConfig {
+            keywords: &[
+                "function", "const", "let", "var", "if", "else", "for", "while", "return",
+                "class", "extends", "new", "this", "typeof", "instanceof", "in", "of", "try",
+                "catch", "finally", "throw", "switch", "case", "default", "break", "continue",
+                "async", "await", "import", "export", "from", "null", "undefined", "true", "false",
+            ],
+            line_comment: Some("//"),
+            block_comment: Some(("/*", "*/")),
+        }),
+        _ => None,
+    }
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    chars: Vec<(usize, char)>,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            source,
+            chars: source.char_indices().collect(),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).map(|&(_, c)| c)
+    }
+
+    fn byte_offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map(|&(b, _)| b)
+            .unwrap_or(self.source.len())
+    }
+
+    fn advance(&mut self) {
+        self.pos += 1;
+    }
+
+    fn starts_with(&self, s: &str) -> bool {
+        self.source[self.byte_offset()..].starts_with(s)
+    }
+}
+
+fn tokenize(source: &str, config: &LanguageConfig) -> Vec<(TokenKind, Range<usize>)> {
+    let mut lexer = Lexer::new(source);
+    let mut tokens = Vec::new();
+
+    while let Some(c) = lexer.peek() {
+        let start = lexer.byte_offset();
+
+        if c.is_whitespace() {
+            while lexer.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+                lexer.advance();
+            }
+            tokens.push((TokenKind::Plain, start..lexer.byte_offset()));
+            continue;
+        }
+
+        if let Some(line_comment) = config.line_comment {
+            if lexer.starts_with(line_comment) {
+                while lexer.peek().map(|c| c != '\n').unwrap_or(false) {
+                    lexer.advance();
+                }
+                tokens.push((TokenKind::Comment, start..lexer.byte_offset()));
+                continue;
+            }
+        }
+
+        if let Some((open, close)) = config.block_comment {
+            if lexer.starts_with(open) {
+                for _ in 0..open.chars().count() {
+                    lexer.advance();
+                }
+                while lexer.peek().is_some() && !lexer.starts_with(close) {
+                    lexer.advance();
+                }
+                for _ in 0..close.chars().count() {
+                    if lexer.peek().is_some() {
+                        lexer.advance();
+                    }
+                }
+                tokens.push((TokenKind::Comment, start..lexer.byte_offset()));
+                continue;
+            }
+        }
+
+        if c == '"' || c == '\'' {
+            let quote = c;
+            lexer.advance();
+            while let Some(ch) = lexer.peek() {
+                if ch == '\\' {
+                    lexer.advance();
+                    if lexer.peek().is_some() {
+                        lexer.advance();
+                    }
+                    continue;
+                }
+                lexer.advance();
+                if ch == quote {
+                    break;
+                }
+            }
+            tokens.push((TokenKind::String, start..lexer.byte_offset()));
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            while lexer
+                .peek()
+                .map(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+                .unwrap_or(false)
+            {
+                lexer.advance();
+            }
+            tokens.push((TokenKind::Number, start..lexer.byte_offset()));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            while lexer
+                .peek()
+                .map(|c| c.is_alphanumeric() || c == '_')
+                .unwrap_or(false)
+            {
+                lexer.advance();
+            }
+            let word = &source[start..lexer.byte_offset()];
+            let kind = if config.keywords.contains(&word) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Ident
+            };
+            tokens.push((kind, start..lexer.byte_offset()));
+            continue;
+        }
+
+        lexer.advance();
+        tokens.push((TokenKind::Op, start..lexer.byte_offset()));
+    }
+
+    tokens
+}
+
+/// Render `source` as a highlighted `<pre><code>` block. Unrecognized
+/// languages fall back to a plain escaped block (no spans, no highlighting).
+pub fn code_block(source: &str, language: &str) -> Element {
+    let code_el = match language_config(language) {
+        Some(config) => {
+            let mut el = code().class(&format!("language-{}", language));
+            for (kind, range) in tokenize(source, &config) {
+                let text = &source[range];
+                el = match kind.class() {
+                    Some(class) => el.child(&span().class(class).text(text).build()),
+                    None => el.text(text),
+                };
+            }
+            el
+        }
+        None => code().text(source),
+    };
+    pre().child(&code_el.build())
+}
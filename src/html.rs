@@ -2,6 +2,8 @@
 /// Provides a composable, type-safe way to construct HTML
 
 use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, Write};
 
 // ============================================================================
 // FULL DOCUMENT BUILDER
@@ -131,10 +133,48 @@ pub fn script(content: &str) -> String {
 // COMPOSABLE ELEMENT BUILDER
 // ============================================================================
 
+/// A child is already-serialized markup (itself well-formed, added via
+/// `.child()`/`.children()`), plain escaped text (added via `.text()`), or a
+/// nested `Element` (added via `.child_element()`). Only the `Element`
+/// variant is visible to `validate`/`serialize`/`write`, since the other two
+/// are opaque strings by the time they reach the tree.
+enum Node {
+    Markup(String),
+    Text(String),
+    Element(Box<Element>),
+}
+
+const VOID_ELEMENTS: &[&str] = &["input", "meta", "link", "br", "img", "hr"];
+
+/// An invalid element tree: `tag` names the element where the problem was
+/// found, `reason` describes what's wrong (e.g. "void element cannot have children").
+#[derive(Debug)]
+pub struct HtmlError {
+    pub tag: String,
+    pub reason: String,
+}
+
+impl fmt::Display for HtmlError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid <{}>: {}", self.tag, self.reason)
+    }
+}
+
+impl std::error::Error for HtmlError {}
+
+impl From<io::Error> for HtmlError {
+    fn from(err: io::Error) -> Self {
+        HtmlError {
+            tag: String::new(),
+            reason: format!("write failed: {}", err),
+        }
+    }
+}
+
 pub struct Element {
     tag: String,
     attrs: HashMap<String, String>,
-    children: Vec<String>,
+    children: Vec<Node>,
     self_closing: bool,
 }
 
@@ -210,19 +250,35 @@ impl Element {
 
     /// Add child element (raw HTML)
     pub fn child(mut self, html: &str) -> Self {
-        self.children.push(html.to_string());
+        self.children.push(Node::Markup(html.to_string()));
+        self
+    }
+
+    /// Add a nested `Element` as a structured child, so `validate`/
+    /// `serialize`/`write` can see into it (unlike `.child()`'s opaque HTML).
+    pub fn child_element(mut self, element: Element) -> Self {
+        self.children.push(Node::Element(Box::new(element)));
+        self
+    }
+
+    /// Add child content sanitized against an allowlist policy — for
+    /// untrusted HTML (user comments, pasted fragments) where `.child()`
+    /// would let arbitrary markup through unchecked.
+    pub fn child_sanitized(mut self, untrusted_html: &str, policy: &crate::sanitize::Policy) -> Self {
+        self.children
+            .push(Node::Markup(crate::sanitize::sanitize(untrusted_html, policy)));
         self
     }
 
     /// Add multiple children at once
     pub fn children(mut self, items: Vec<String>) -> Self {
-        self.children.extend(items);
+        self.children.extend(items.into_iter().map(Node::Markup));
         self
     }
 
     /// Add text content (auto-escaped)
     pub fn text(mut self, text: &str) -> Self {
-        self.children.push(escape_html(text));
+        self.children.push(Node::Text(escape_html(text)));
         self
     }
 
@@ -234,9 +290,111 @@ impl Element {
         f(self)
     }
 
-    /// Build the final HTML string
+    /// Build the final HTML string. Unlike `serialize`, this never validates
+    /// structure — kept around for existing call sites that just want markup.
     pub fn build(self) -> String {
-        let attrs = if self.attrs.is_empty() {
+        let attrs = self.build_attrs();
+
+        if self.self_closing {
+            format!("<{}{}>", self.tag, attrs)
+        } else {
+            let children: String = self
+                .children
+                .into_iter()
+                .map(|node| match node {
+                    Node::Markup(s) | Node::Text(s) => s,
+                    Node::Element(el) => el.build(),
+                })
+                .collect();
+            format!("<{}{}>{}</{}>", self.tag, attrs, children, self.tag)
+        }
+    }
+
+    /// Validate element structure: void elements must not have children,
+    /// `<ul>`/`<ol>` may only contain `<li>`, table rows only `<td>`/`<th>`,
+    /// and `<a>` must carry `href`. Only `child_element()` children are
+    /// visible to this check — `.child()`/`.text()` content is opaque.
+    fn validate(&self) -> Result<(), HtmlError> {
+        if VOID_ELEMENTS.contains(&self.tag.as_str()) && !self.children.is_empty() {
+            return Err(HtmlError {
+                tag: self.tag.clone(),
+                reason: "void element cannot have children".to_string(),
+            });
+        }
+
+        if self.tag == "a" && !self.attrs.contains_key("href") {
+            return Err(HtmlError {
+                tag: self.tag.clone(),
+                reason: "missing required attribute `href`".to_string(),
+            });
+        }
+
+        let allowed_children: Option<&[&str]> = match self.tag.as_str() {
+            "ul" | "ol" => Some(&["li"]),
+            "tr" => Some(&["td", "th"]),
+            _ => None,
+        };
+
+        for node in &self.children {
+            if let Node::Element(child) = node {
+                if let Some(allowed) = allowed_children {
+                    if !allowed.contains(&child.tag.as_str()) {
+                        return Err(HtmlError {
+                            tag: self.tag.clone(),
+                            reason: format!("<{}> cannot contain <{}>", self.tag, child.tag),
+                        });
+                    }
+                }
+                child.validate()?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the tree and serialize it to a `String`.
+    pub fn serialize(&self) -> Result<String, HtmlError> {
+        let mut buf = Vec::new();
+        self.write(&mut buf)?;
+        Ok(String::from_utf8(buf).expect("HTML output is always valid UTF-8"))
+    }
+
+    /// Validate the tree and stream it to `w`, without allocating one big
+    /// `String` up front.
+    pub fn write<W: Write>(&self, w: &mut W) -> Result<(), HtmlError> {
+        self.validate()?;
+        self.write_unchecked(w)
+    }
+
+    /// Serialize without consuming `self` or validating — used where a
+    /// nested element needs rendering from behind a shared reference.
+    fn build_ref(&self) -> String {
+        let mut buf = Vec::new();
+        self.write_unchecked(&mut buf).expect("writing to a Vec<u8> cannot fail");
+        String::from_utf8(buf).expect("HTML output is always valid UTF-8")
+    }
+
+    fn write_unchecked<W: Write>(&self, w: &mut W) -> Result<(), HtmlError> {
+        let attrs = self.build_attrs();
+
+        if self.self_closing {
+            write!(w, "<{}{}>", self.tag, attrs)?;
+            return Ok(());
+        }
+
+        write!(w, "<{}{}>", self.tag, attrs)?;
+        for node in &self.children {
+            match node {
+                Node::Markup(s) | Node::Text(s) => write!(w, "{}", s)?,
+                Node::Element(el) => el.write_unchecked(w)?,
+            }
+        }
+        write!(w, "</{}>", self.tag)?;
+        Ok(())
+    }
+
+    fn build_attrs(&self) -> String {
+        if self.attrs.is_empty() {
             String::new()
         } else {
             format!(
@@ -247,22 +405,172 @@ impl Element {
                     .collect::<Vec<_>>()
                     .join(" ")
             )
-        };
+        }
+    }
+
+    /// Render at most `max_len` bytes of visible text while always
+    /// returning well-formed HTML with every opened tag closed — useful for
+    /// search snippets, list previews, and truncated feeds. Tag markup
+    /// itself (already-built `Node::Markup` content, and every open/close
+    /// tag) never counts against the budget, only text does - the budget is
+    /// shared across the whole tree, so nested elements (`<p>…<strong>…`)
+    /// are walked into and truncated too, not just top-level text; once it's
+    /// exhausted, remaining content at any depth is dropped and an ellipsis
+    /// is appended at the exact cut point before every opened tag closes.
+    pub fn build_limited(self, max_len: usize) -> String {
+        let attrs = self.build_attrs();
 
         if self.self_closing {
-            format!("<{}{}>", self.tag, attrs)
-        } else {
-            format!(
-                "<{}{}>{}</{}>",
-                self.tag,
-                attrs,
-                self.children.join(""),
-                self.tag
-            )
+            return format!("<{}{}>", self.tag, attrs);
+        }
+
+        let mut remaining = max_len;
+        let mut truncated = false;
+        let body = Self::build_children_limited(&self.children, &mut remaining, &mut truncated);
+
+        format!("<{}{}>{}</{}>", self.tag, attrs, body, self.tag)
+    }
+
+    fn build_children_limited(children: &[Node], remaining: &mut usize, truncated: &mut bool) -> String {
+        let mut body = String::new();
+
+        for child in children {
+            if *truncated {
+                break;
+            }
+            match child {
+                Node::Markup(s) => body.push_str(s),
+                Node::Text(s) => {
+                    if *remaining == 0 {
+                        body.push('…');
+                        *truncated = true;
+                        break;
+                    }
+                    if s.len() <= *remaining {
+                        body.push_str(s);
+                        *remaining -= s.len();
+                    } else {
+                        let mut cut = *remaining;
+                        while cut > 0 && !s.is_char_boundary(cut) {
+                            cut -= 1;
+                        }
+                        body.push_str(&s[..cut]);
+                        body.push('…');
+                        *remaining = 0;
+                        *truncated = true;
+                    }
+                }
+                Node::Element(el) => {
+                    let attrs = el.build_attrs();
+                    if el.self_closing {
+                        body.push_str(&format!("<{}{}>", el.tag, attrs));
+                        continue;
+                    }
+                    body.push_str(&format!("<{}{}>", el.tag, attrs));
+                    body.push_str(&Self::build_children_limited(&el.children, remaining, truncated));
+                    body.push_str(&format!("</{}>", el.tag));
+                }
+            }
+        }
+
+        body
+    }
+
+    /// Serialize this tree for a given output `Target`, dispatching both
+    /// escaping and tag-to-markup mapping on it. `Node::Markup` content is
+    /// always passed through verbatim regardless of target, since it's
+    /// already-serialized markup the caller is responsible for.
+    pub fn render(&self, target: Target) -> String {
+        match target {
+            Target::Html => self.build_ref(),
+            Target::Latex { drop_unmapped } => self.render_latex(drop_unmapped),
+        }
+    }
+
+    fn render_latex(&self, drop_unmapped: bool) -> String {
+        let inner: String = self
+            .children
+            .iter()
+            .map(|node| match node {
+                Node::Markup(s) => s.clone(),
+                Node::Text(s) => escape_latex(&unescape_html(s)),
+                Node::Element(el) => el.render_latex(drop_unmapped),
+            })
+            .collect();
+
+        match latex_wrapper(&self.tag, &self.attrs) {
+            Some((open, close)) => format!("{}{}{}", open, inner, close),
+            None if drop_unmapped => String::new(),
+            None => inner,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    Html,
+    /// `drop_unmapped` controls what happens to tags with no LaTeX mapping
+    /// (e.g. `div`, `span`): `false` keeps their content inline, `true`
+    /// drops the element (and its content) entirely.
+    Latex { drop_unmapped: bool },
+}
+
+/// Maps an HTML tag to a `(open, close)` LaTeX wrapper, or `None` if the tag
+/// has no direct LaTeX counterpart.
+fn latex_wrapper(tag: &str, attrs: &HashMap<String, String>) -> Option<(String, String)> {
+    match tag {
+        "h1" => Some(("\\section{".to_string(), "}".to_string())),
+        "h2" => Some(("\\subsection{".to_string(), "}".to_string())),
+        "h3" => Some(("\\subsubsection{".to_string(), "}".to_string())),
+        "h4" | "h5" | "h6" => Some(("\\paragraph{".to_string(), "}".to_string())),
+        "p" => Some((String::new(), "\n\n".to_string())),
+        "ul" => Some(("\\begin{itemize}\n".to_string(), "\n\\end{itemize}".to_string())),
+        "ol" => Some(("\\begin{enumerate}\n".to_string(), "\n\\end{enumerate}".to_string())),
+        "li" => Some(("\\item ".to_string(), String::new())),
+        "strong" | "b" => Some(("\\textbf{".to_string(), "}".to_string())),
+        "em" | "i" => Some(("\\textit{".to_string(), "}".to_string())),
+        "blockquote" => Some(("\\begin{quote}\n".to_string(), "\n\\end{quote}".to_string())),
+        "code" => Some(("\\texttt{".to_string(), "}".to_string())),
+        "pre" => Some(("\\begin{verbatim}\n".to_string(), "\n\\end{verbatim}".to_string())),
+        "br" => Some((String::new(), "\\\\\n".to_string())),
+        "hr" => Some(("\\noindent\\hrulefill\n".to_string(), String::new())),
+        "a" => {
+            let href = attrs.get("href").map(|s| s.as_str()).unwrap_or("");
+            Some((format!("\\href{{{}}}{{", escape_latex(href)), "}".to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// Escape LaTeX special characters (`& % $ # _ {  } ~ ^ \`) in plain text.
+fn escape_latex(input: &str) -> String {
+    let mut out = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// `Node::Text` is stored HTML-escaped (see `.text()`); undo that before
+/// re-escaping for a non-HTML target.
+fn unescape_html(input: &str) -> String {
+    input
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
 // ============================================================================
 // ELEMENT CONSTRUCTORS
 // ============================================================================
@@ -373,6 +681,44 @@ pub fn th() -> Element {
     Element::new("th")
 }
 
+pub fn pre() -> Element {
+    Element::new("pre")
+}
+
+pub fn code() -> Element {
+    Element::new("code")
+}
+
+pub fn em() -> Element {
+    Element::new("em")
+}
+
+pub fn strong() -> Element {
+    Element::new("strong")
+}
+
+pub fn blockquote() -> Element {
+    Element::new("blockquote")
+}
+
+pub fn hr() -> Element {
+    let mut el = Element::new("hr");
+    el.self_closing = true;
+    el
+}
+
+pub fn br() -> Element {
+    let mut el = Element::new("br");
+    el.self_closing = true;
+    el
+}
+
+pub fn img() -> Element {
+    let mut el = Element::new("img");
+    el.self_closing = true;
+    el
+}
+
 // ============================================================================
 // UTILITIES
 // ============================================================================
@@ -432,3 +778,65 @@ pub fn list(items: Vec<String>) -> String {
         )
         .build()
 }
+
+/// A node in a hierarchical site map: leaves carry a `path` and render as a
+/// link, branches render their `name` as a heading over a nested `<ul>` of
+/// `children`. Build one with `NavEntry::leaf`/`NavEntry::branch`.
+pub struct NavEntry {
+    pub name: String,
+    pub path: Option<String>,
+    pub children: Vec<NavEntry>,
+}
+
+impl NavEntry {
+    pub fn leaf(name: &str, path: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            path: Some(path.to_string()),
+            children: Vec::new(),
+        }
+    }
+
+    pub fn branch(name: &str, children: Vec<NavEntry>) -> Self {
+        Self {
+            name: name.to_string(),
+            path: None,
+            children,
+        }
+    }
+
+    fn contains_path(&self, active_path: &str) -> bool {
+        self.path.as_deref() == Some(active_path)
+            || self.children.iter().any(|child| child.contains_path(active_path))
+    }
+
+    fn render(&self, active_path: Option<&str>) -> String {
+        if self.children.is_empty() {
+            let is_active = active_path
+                .map(|p| self.path.as_deref() == Some(p))
+                .unwrap_or(false);
+            let mut link = a().href(self.path.as_deref().unwrap_or("#"));
+            if is_active {
+                link = link.class("active").attr("aria-current", "page");
+            }
+            return li().child(&link.text(&self.name).build()).build();
+        }
+
+        let expanded = active_path.map(|p| self.contains_path(p)).unwrap_or(false);
+        let heading_class = if expanded { "nav-heading expanded" } else { "nav-heading" };
+        let heading = span().class(heading_class).text(&self.name).build();
+        let children: Vec<String> = self.children.iter().map(|child| child.render(active_path)).collect();
+        let sublist = ul().children(children).build();
+
+        li().child(&heading).child(&sublist).build()
+    }
+}
+
+/// Render a hierarchical site map as a `<nav>` of nested `<ul>`/`<li>`/`<a>`
+/// elements. When `active_path` matches a leaf's `path`, that entry gets an
+/// `active` class and `aria-current="page"`, and its ancestor branches get
+/// an `expanded` class so a collapsible sidebar can auto-open to it.
+pub fn nav_tree(entries: &[NavEntry], active_path: Option<&str>) -> String {
+    let items: Vec<String> = entries.iter().map(|entry| entry.render(active_path)).collect();
+    nav().child(&ul().children(items).build()).build()
+}
@@ -0,0 +1,19 @@
+//! Library surface for the `hrml` binary: the CLI (`src/main.rs`) and
+//! integration tests both build against this crate root, and Cargo wires
+//! the binary to it automatically since they share a package name. Having
+//! a real lib target (rather than everything living in `main.rs`) is what
+//! lets `hrml_derive` depend on `hrml::template` for its compile-time
+//! codegen, and lets `tests/` exercise modules directly.
+pub mod assets;
+pub mod config;
+pub mod router;
+pub mod template;
+pub mod python;
+pub mod html;
+pub mod db;
+pub mod migrations;
+pub mod pool;
+pub mod markdown;
+pub mod sanitize;
+pub mod highlight;
+pub mod lsp;
@@ -0,0 +1,430 @@
+/// `hrml-lsp`: an editor-integration language server for `.hrml` templates,
+/// reached via `hrml lsp` (it speaks the Language Server Protocol over
+/// stdio, same as a standalone `hrml-lsp` binary would). Built on top of
+/// the existing span-tracking `template::Parser`, so every position it
+/// reports maps onto exact source ranges; each open document's parsed
+/// `Node` tree is cached and only re-parsed on change, not on every request.
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use tokio::sync::RwLock;
+use tower_lsp::jsonrpc::Result as RpcResult;
+use tower_lsp::lsp_types::*;
+use tower_lsp::{async_trait, Client, LanguageServer, LspService, Server};
+
+use crate::template::{self, Node, Span};
+
+/// Tag name, one-line hover description, and the attribute keys `render_node`
+/// actually looks at for it (see `template.rs`'s `render_node`/`render_for`).
+const TAGS: &[(&str, &str, &[&str])] = &[
+    ("if", "Conditionally renders its children; `<?else?>` inside splits the true/false branches.", &["cond"]),
+    ("for", "Iterates `in=\"item items\"` (array) or `in=\"k v map\"` (object), binding `loop.*` metadata each pass.", &["in"]),
+    ("set", "Binds a context variable, either from `value` or from its rendered children; `raw` marks it pre-escaped.", &["id", "value", "raw"]),
+    ("get", "Interpolates a bound variable (`id`) or an expression (`expr`); escaped unless `raw`.", &["id", "expr", "raw"]),
+    ("btn", "Renders a `<button>` wired to an HTMX-style `data-get`/`data-post` endpoint.", &["get", "post", "target", "swap"]),
+    ("link", "Renders an `<a>` wired to a `data-get` endpoint instead of navigating.", &["get", "target", "swap"]),
+    ("form", "Renders a `<form>` wired to a `data-post` endpoint.", &["post", "target", "swap"]),
+    ("asset", "Rewrites `path` (relative to `static/`) to its fingerprinted `/static/...` URL.", &["path"]),
+];
+
+fn tag_info(name: &str) -> Option<&'static (&'static str, &'static str, &'static [&'static str])> {
+    TAGS.iter().find(|(tag, ..)| *tag == name)
+}
+
+struct Document {
+    text: String,
+    nodes: Vec<Node>,
+}
+
+pub struct Backend {
+    client: Client,
+    base_path: PathBuf,
+    documents: RwLock<HashMap<Url, Document>>,
+}
+
+impl Backend {
+    /// Relative template path (what `Engine::render`/`<?load file=...?>`
+    /// expect) for a document URI under `base_path`, if it's inside it.
+    fn relative_path(&self, uri: &Url) -> Option<String> {
+        let path = uri.to_file_path().ok()?;
+        let rel = path.strip_prefix(&self.base_path).ok()?;
+        Some(rel.to_string_lossy().replace('\\', "/"))
+    }
+
+    async fn publish_diagnostics(&self, uri: Url, text: String) {
+        let rel_path = self.relative_path(&uri).unwrap_or_else(|| uri.to_string());
+        let mut diagnostics = Vec::new();
+
+        match template::parse_source(&rel_path, &text) {
+            Ok(nodes) => {
+                diagnostics.extend(unclosed_tag_diagnostics(&text));
+                diagnostics.extend(undefined_slot_diagnostics(&nodes, &text));
+
+                // Run the full load/import graph to catch circular
+                // dependencies and other file-level resolution failures.
+                let engine = template::Engine::new(&self.base_path.to_string_lossy());
+                if let Err(diag) = engine.resolve_for_tooling(&rel_path) {
+                    diagnostics.push(diagnostic_from(&diag, &text));
+                }
+
+                self.documents.write().await.insert(uri.clone(), Document { text, nodes });
+            }
+            Err(diag) => {
+                diagnostics.push(diagnostic_from(&diag, &text));
+            }
+        }
+
+        self.client.publish_diagnostics(uri, diagnostics, None).await;
+    }
+
+    /// Walk the cached tree for the node whose span contains `offset`,
+    /// preferring the innermost (deepest) match.
+    fn node_at<'a>(nodes: &'a [Node], offset: usize) -> Option<&'a Node> {
+        for node in nodes {
+            let (span, children) = match node {
+                Node::Text(_, span) => (*span, None),
+                Node::Element { span, children, .. } => (*span, Some(children.as_slice())),
+                Node::VoidElement { span, .. } => (*span, None),
+            };
+            if offset < span.start || offset > span.end {
+                continue;
+            }
+            if let Some(children) = children {
+                if let Some(found) = Self::node_at(children, offset) {
+                    return Some(found);
+                }
+            }
+            return Some(node);
+        }
+        None
+    }
+}
+
+#[async_trait]
+impl LanguageServer for Backend {
+    async fn initialize(&self, _: InitializeParams) -> RpcResult<InitializeResult> {
+        Ok(InitializeResult {
+            capabilities: ServerCapabilities {
+                text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+                completion_provider: Some(CompletionOptions::default()),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                ..ServerCapabilities::default()
+            },
+            server_info: Some(ServerInfo {
+                name: "hrml-lsp".to_string(),
+                version: None,
+            }),
+        })
+    }
+
+    async fn initialized(&self, _: InitializedParams) {
+        self.client.log_message(MessageType::INFO, "hrml-lsp ready").await;
+    }
+
+    async fn shutdown(&self) -> RpcResult<()> {
+        Ok(())
+    }
+
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        self.publish_diagnostics(params.text_document.uri, params.text_document.text).await;
+    }
+
+    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+        // Full sync: the last change event carries the whole document.
+        if let Some(change) = params.content_changes.pop() {
+            self.publish_diagnostics(params.text_document.uri, change.text).await;
+        }
+    }
+
+    async fn did_save(&self, params: DidSaveTextDocumentParams) {
+        if let Some(text) = params.text {
+            self.publish_diagnostics(params.text_document.uri, text).await;
+        }
+    }
+
+    async fn completion(&self, params: CompletionParams) -> RpcResult<Option<CompletionResponse>> {
+        let uri = params.text_document_position.text_document.uri;
+        let position = params.text_document_position.position;
+        let documents = self.documents.read().await;
+        let Some(doc) = documents.get(&uri) else { return Ok(None) };
+        // `position_to_offset` returns a char offset (the parser's `Span`s are
+        // char-based), so it has to be converted to a byte index before it can
+        // slice `doc.text` - using it directly would panic on any multi-byte
+        // character appearing before the cursor.
+        let offset = position_to_offset(&doc.text, position);
+        let byte_offset = doc
+            .text
+            .char_indices()
+            .nth(offset)
+            .map(|(i, _)| i)
+            .unwrap_or(doc.text.len());
+        let before = &doc.text[..byte_offset];
+
+        // Inside an open `<?tag` with no `?>` yet, and past the tag name
+        // (a space after it): offer that tag's attribute keys. Otherwise,
+        // right after `<?` with no name typed: offer tag names.
+        let items = match before.rfind("<?") {
+            Some(tag_start) if !before[tag_start..].contains("?>") => {
+                let after_marker = &before[tag_start + 2..];
+                match after_marker.find(char::is_whitespace) {
+                    Some(space) => {
+                        let name = &after_marker[..space];
+                        tag_info(name)
+                            .map(|(_, _, attrs)| {
+                                attrs
+                                    .iter()
+                                    .map(|attr| CompletionItem {
+                                        label: attr.to_string(),
+                                        kind: Some(CompletionItemKind::FIELD),
+                                        ..Default::default()
+                                    })
+                                    .collect()
+                            })
+                            .unwrap_or_default()
+                    }
+                    None => TAGS
+                        .iter()
+                        .map(|(name, desc, _)| CompletionItem {
+                            label: name.to_string(),
+                            kind: Some(CompletionItemKind::KEYWORD),
+                            detail: Some(desc.to_string()),
+                            ..Default::default()
+                        })
+                        .collect(),
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(Some(CompletionResponse::Array(items)))
+    }
+
+    async fn hover(&self, params: HoverParams) -> RpcResult<Option<Hover>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let documents = self.documents.read().await;
+        let Some(doc) = documents.get(&uri) else { return Ok(None) };
+        let offset = position_to_offset(&doc.text, position);
+
+        let Some(node) = Self::node_at(&doc.nodes, offset) else { return Ok(None) };
+        let (name, span) = match node {
+            Node::Element { name, span, .. } => (name, *span),
+            Node::VoidElement { name, span, .. } => (name, *span),
+            Node::Text(..) => return Ok(None),
+        };
+        let Some((_, desc, attrs)) = tag_info(name) else { return Ok(None) };
+
+        Ok(Some(Hover {
+            contents: HoverContents::Scalar(MarkedString::String(format!(
+                "`<?{}?>`\n\n{}\n\nAttributes: {}",
+                name,
+                desc,
+                if attrs.is_empty() { "none".to_string() } else { attrs.join(", ") }
+            ))),
+            range: Some(span_to_range(&doc.text, span)),
+        }))
+    }
+
+    async fn goto_definition(&self, params: GotoDefinitionParams) -> RpcResult<Option<GotoDefinitionResponse>> {
+        let uri = params.text_document_position_params.text_document.uri;
+        let position = params.text_document_position_params.position;
+        let documents = self.documents.read().await;
+        let Some(doc) = documents.get(&uri) else { return Ok(None) };
+        let offset = position_to_offset(&doc.text, position);
+
+        let Some(node) = Self::node_at(&doc.nodes, offset) else { return Ok(None) };
+
+        match node {
+            Node::VoidElement { name, attrs, .. } if name == "load" || name == "import" => {
+                let Some(file) = attrs.get("file") else { return Ok(None) };
+                let target = self.base_path.join(file);
+                let Ok(target_uri) = Url::from_file_path(&target) else { return Ok(None) };
+                Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+                    target_uri,
+                    Range::new(Position::new(0, 0), Position::new(0, 0)),
+                ))))
+            }
+            Node::Element { name, attrs, .. } if name == "slot" => {
+                // Only resolved within the same document: the caller that
+                // fills this slot via `<?block slot="..."?>` usually lives
+                // in whichever page loads this layout, which isn't known
+                // statically from here.
+                let Some(id) = attrs.get("id") else { return Ok(None) };
+                for other in &doc.nodes {
+                    if let Node::Element { name, attrs, span, .. } = other {
+                        if name == "block" && attrs.get("slot") == Some(id) {
+                            return Ok(Some(GotoDefinitionResponse::Scalar(Location::new(
+                                uri.clone(),
+                                span_to_range(&doc.text, *span),
+                            ))));
+                        }
+                    }
+                }
+                Ok(None)
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+/// Independent stack-based scan for unclosed `<?tag?>...<?/tag?>` pairs.
+/// The renderer's own parser is deliberately lenient here (a missing close
+/// just truncates the tree rather than failing the whole render), so this
+/// check exists purely for editor feedback.
+fn unclosed_tag_diagnostics(source: &str) -> Vec<Diagnostic> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut pos = 0;
+    let mut stack: Vec<(String, usize)> = Vec::new();
+
+    while pos < chars.len() {
+        if chars[pos] == '<' && chars.get(pos + 1) == Some(&'?') {
+            if chars.get(pos + 2) == Some(&'/') {
+                let mut i = pos + 3;
+                let name_start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '-') {
+                    i += 1;
+                }
+                let name: String = chars[name_start..i].iter().collect();
+                while i < chars.len() && chars[i] != '>' {
+                    i += 1;
+                }
+                pos = (i + 1).min(chars.len());
+                if stack.last().map(|(top, _)| top.as_str()) == Some(name.as_str()) {
+                    stack.pop();
+                }
+                continue;
+            }
+
+            let start = pos;
+            let mut i = pos + 2;
+            let name_start = i;
+            while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '-') {
+                i += 1;
+            }
+            let name: String = chars[name_start..i].iter().collect();
+            while i < chars.len() && chars[i] != '>' {
+                i += 1;
+            }
+            pos = (i + 1).min(chars.len());
+            if !name.is_empty() && !Node::is_void(&name) {
+                stack.push((name, start));
+            }
+            continue;
+        }
+        pos += 1;
+    }
+
+    stack
+        .into_iter()
+        .map(|(name, start)| {
+            let span = Span { start, end: (start + name.len() + 2).min(chars.len()) };
+            Diagnostic {
+                range: span_to_range(source, span),
+                severity: Some(DiagnosticSeverity::ERROR),
+                message: format!("unclosed <?{}?> (no matching <?/{}?> found)", name, name),
+                source: Some("hrml-lsp".to_string()),
+                ..Default::default()
+            }
+        })
+        .collect()
+}
+
+/// `<?slot id="x"?>` with no top-level `<?block slot="x"?>` in the *same*
+/// document renders its own default content silently; flag it so an author
+/// notices a slot went unfilled (a cross-file layout/page split is the
+/// common case and isn't flagged, since the filling block lives elsewhere).
+fn undefined_slot_diagnostics(nodes: &[Node], source: &str) -> Vec<Diagnostic> {
+    let block_slots: Vec<&String> = nodes
+        .iter()
+        .filter_map(|n| match n {
+            Node::Element { name, attrs, .. } if name == "block" => attrs.get("slot"),
+            _ => None,
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    collect_undefined_slots(nodes, &block_slots, source, &mut out);
+    out
+}
+
+fn collect_undefined_slots(nodes: &[Node], block_slots: &[&String], source: &str, out: &mut Vec<Diagnostic>) {
+    for node in nodes {
+        match node {
+            Node::Element { name, attrs, children, span } => {
+                if name == "slot" {
+                    if let Some(id) = attrs.get("id") {
+                        if !block_slots.iter().any(|slot| *slot == id) {
+                            out.push(Diagnostic {
+                                range: span_to_range(source, *span),
+                                severity: Some(DiagnosticSeverity::HINT),
+                                message: format!("no <?block slot=\"{}\"?> in this file (may be filled by a caller)", id),
+                                source: Some("hrml-lsp".to_string()),
+                                ..Default::default()
+                            });
+                        }
+                    }
+                }
+                collect_undefined_slots(children, block_slots, source, out);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn diagnostic_from(diag: &template::Diagnostic, source: &str) -> Diagnostic {
+    Diagnostic {
+        range: span_to_range(source, diag.primary_span()),
+        severity: Some(DiagnosticSeverity::ERROR),
+        message: diag.message().to_string(),
+        source: Some("hrml-lsp".to_string()),
+        ..Default::default()
+    }
+}
+
+fn position_to_offset(source: &str, position: Position) -> usize {
+    let mut offset = 0;
+    for (i, line) in source.split('\n').enumerate() {
+        if i as u32 == position.line {
+            return offset + (position.character as usize).min(line.chars().count());
+        }
+        offset += line.chars().count() + 1; // +1 for the '\n'
+    }
+    source.chars().count()
+}
+
+fn span_to_range(source: &str, span: Span) -> Range {
+    Range::new(offset_to_position(source, span.start), offset_to_position(source, span.end))
+}
+
+fn offset_to_position(source: &str, offset: usize) -> Position {
+    let mut line = 0u32;
+    let mut col = 0u32;
+    for (i, c) in source.chars().enumerate() {
+        if i >= offset {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            col = 0;
+        } else {
+            col += 1;
+        }
+    }
+    Position::new(line, col)
+}
+
+/// Run the language server over stdio, rooted at `base_path` for resolving
+/// `<?load?>`/`<?import?>` targets the same way `Engine` does.
+pub async fn run_stdio(base_path: PathBuf) {
+    let stdin = tokio::io::stdin();
+    let stdout = tokio::io::stdout();
+
+    let (service, socket) = LspService::new(|client| Backend {
+        client,
+        base_path,
+        documents: RwLock::new(HashMap::new()),
+    });
+
+    Server::new(stdin, stdout, socket).serve(service).await;
+}
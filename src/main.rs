@@ -4,27 +4,42 @@ use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 
-mod config;
-mod router;
-mod template;
-mod python;
-mod html;
-mod db;
+use hrml::{assets, config, db, lsp, migrations, pool, python, router, template};
 
 use axum::{
     body::Body,
     extract::{Path as AxumPath, State},
     http::{Request, StatusCode},
-    response::{Html, IntoResponse, Response},
+    response::{
+        sse::{Event, Sse},
+        Html, IntoResponse, Response,
+    },
     routing::get,
     Router,
 };
-use std::sync::Arc;
-use tower_http::services::ServeDir;
+use bytes::Bytes;
+use futures::stream::StreamExt;
+use std::convert::Infallible;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
 
 const VERSION: &str = "0.1.0";
 const HRML_JS: &str = include_str!("runtime/client.js");
 
+/// Appended to `HRML_JS` in dev mode only: connects to `/hrml/live` and
+/// reloads the page when the file watcher broadcasts a change.
+const LIVE_RELOAD_CLIENT_JS: &str = r#"
+(function () {
+    var source = new EventSource("/hrml/live");
+    source.onmessage = function (event) {
+        console.log("[hrml] reloading, changed:", event.data);
+        window.location.reload();
+    };
+})();
+"#;
+
 fn print_help() {
     println!("HRML - Minimal Web Framework v{}", VERSION);
     println!();
@@ -36,6 +51,7 @@ fn print_help() {
     println!("  serve [path]        Run production server");
     println!("  build [path]        Build static site for deployment");
     println!("  check [path]        Validate templates and configuration");
+    println!("  lsp [path]          Run the hrml-lsp language server over stdio");
     println!("  version             Show version information");
     println!("  help                Show this help message");
     println!();
@@ -45,6 +61,7 @@ fn print_help() {
     println!("  hrml serve ./myapp          Serve project from ./myapp");
     println!("  hrml build ./myapp          Build static site from ./myapp");
     println!("  hrml check                  Validate current project");
+    println!("  hrml lsp                    Run the language server (for editor integration)");
 }
 
 fn create_project(name: &str) -> io::Result<()> {
@@ -84,6 +101,9 @@ static = "static"
 name = "{}"
 description = "A web application built with HRML"
 favicon = "/static/favicon.ico"
+
+[build]
+out_dir = "dist"
 "#, name, name);
     fs::write(project_path.join("hrml.toml"), config)?;
     
@@ -393,22 +413,229 @@ fn validate_project(path: &Path) -> Result<(), String> {
     
     // Validate template engine can be created
     let engine = template::Engine::new(&templates_path.to_string_lossy());
-    
+
     // Try to render index
     match engine.render("pages/index.hrml", &serde_json::json!({})) {
         Ok(_) => println!("[OK] Index template renders successfully"),
         Err(e) => eprintln!("[WARNING] Index template failed to render: {}", e),
     }
-    
+
+    // Report pending migrations without applying them - `check` should
+    // never mutate the database.
+    let db_path = path.join(&config.database.path);
+    let migrations_path = path.join(&config.migrations_path);
+    match rusqlite::Connection::open(&db_path) {
+        Ok(conn) => match migrations::pending(&conn, &migrations_path.to_string_lossy()) {
+            Ok(pending) if pending.is_empty() => println!("[OK] No pending migrations"),
+            Ok(pending) => {
+                eprintln!("[WARNING] {} pending migration(s):", pending.len());
+                for migration in &pending {
+                    eprintln!("  - {} {}", migration.version, migration.name);
+                }
+            }
+            Err(e) => eprintln!("[WARNING] Failed to check migrations: {}", e),
+        },
+        Err(e) => eprintln!("[WARNING] Failed to open database '{}': {}", db_path.display(), e),
+    }
+
+    // Report route-table problems the implicit-routing fallback would mask:
+    // a route pointing at a template that was never created, or a page
+    // template an explicit route has made unreachable.
+    match router::RouteTable::load(path) {
+        Ok(routes) => {
+            let dangling = routes.dangling(&templates_path);
+            if dangling.is_empty() {
+                println!("[OK] No dangling routes");
+            } else {
+                eprintln!("[WARNING] {} route(s) point at a missing template:", dangling.len());
+                for route in &dangling {
+                    eprintln!("  - {} -> {:?}", route.pattern, route.template);
+                }
+            }
+
+            let unreachable = routes.unreachable_templates(&templates_path.join("pages"));
+            if !unreachable.is_empty() {
+                eprintln!("[WARNING] {} page template(s) shadowed by an explicit route:", unreachable.len());
+                for template in &unreachable {
+                    eprintln!("  - {}", template);
+                }
+            }
+        }
+        Err(e) => eprintln!("[WARNING] Failed to load route table: {}", e),
+    }
+
     println!("[OK] Project validation complete");
     Ok(())
 }
 
+/// `hrml build`: pre-renders every page under `templates/pages` (or the
+/// `[build].routes` allow-list, for pages that need an explicit skip) to a
+/// static `dist/`-style tree, mirroring the route structure the dev server
+/// would otherwise serve dynamically (`pages/about.hrml` ->
+/// `<out_dir>/about/index.html`).
+fn build_project(path: &Path) -> Result<(), String> {
+    let config_path = path.join("hrml.toml");
+    let config = config::Config::load(&config_path.to_string_lossy()).unwrap_or_default();
+
+    let templates_path = path.join(&config.templates_path);
+    let pages_path = templates_path.join("pages");
+    if !pages_path.exists() {
+        return Err(format!("Pages directory not found: {}", pages_path.display()));
+    }
+
+    let out_dir = path.join(&config.build.out_dir);
+    fs::create_dir_all(&out_dir).map_err(|e| format!("Failed to create '{}': {}", out_dir.display(), e))?;
+
+    let static_path = path.join(&config.static_path);
+    let asset_manifest = assets::AssetManifest::build(&static_path).unwrap_or_else(|e| {
+        eprintln!("[WARNING] Failed to build asset manifest: {}", e);
+        assets::AssetManifest::default()
+    });
+
+    let engine = template::Engine::new(&templates_path.to_string_lossy())
+        .with_site_name(config.site_name.clone())
+        .with_description(config.site_description.clone())
+        .with_favicon(config.favicon.clone())
+        .with_asset_manifest(asset_manifest.clone());
+    let python_runtime = python::Runtime::new(&path.join(&config.endpoints_path).to_string_lossy());
+
+    let routes = match &config.build.routes {
+        Some(routes) => routes.clone(),
+        None => discover_page_routes(&pages_path)?,
+    };
+
+    let mut failures = 0;
+    for route in &routes {
+        let template_path = format!("pages/{}.hrml", route);
+        let data = python_runtime
+            .call_endpoint(&format!("/api/{}", route), &serde_json::json!({}))
+            .unwrap_or_else(|_| serde_json::json!({}));
+
+        match engine.render(&template_path, &data) {
+            Ok(html) => {
+                let out_path = route_out_path(&out_dir, route);
+                if let Some(parent) = out_path.parent() {
+                    fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+                }
+                fs::write(&out_path, html).map_err(|e| format!("Failed to write '{}': {}", out_path.display(), e))?;
+                println!("[OK] {} -> {}", template_path, out_path.display());
+            }
+            Err(e) => {
+                eprintln!("[ERROR] {} failed to render: {}", template_path, e);
+                failures += 1;
+            }
+        }
+    }
+
+    if static_path.exists() {
+        let out_static = out_dir.join("static");
+        copy_dir_all(&static_path, &out_static)
+            .map_err(|e| format!("Failed to copy static assets: {}", e))?;
+
+        // `<?asset?>` URLs resolve to fingerprinted paths, so the build
+        // output needs a copy under the hashed name too - alongside the
+        // original, not instead of it, so unfingerprinted links keep working.
+        for (logical, fingerprinted) in asset_manifest.fingerprinted_paths() {
+            let src = out_static.join(logical);
+            let dst = out_static.join(fingerprinted);
+            if let Some(parent) = dst.parent() {
+                fs::create_dir_all(parent).map_err(|e| format!("Failed to create '{}': {}", parent.display(), e))?;
+            }
+            fs::copy(&src, &dst).map_err(|e| format!("Failed to write fingerprinted asset '{}': {}", dst.display(), e))?;
+        }
+    }
+
+    fs::write(out_dir.join("hrml.js"), HRML_JS).map_err(|e| format!("Failed to write hrml.js: {}", e))?;
+
+    if failures > 0 {
+        return Err(format!("{} page(s) failed to render", failures));
+    }
+
+    println!("[OK] Built {} page(s) to {}", routes.len(), out_dir.display());
+    Ok(())
+}
+
+/// Walks `pages_path` for `.hrml` files, turning each into a route string
+/// relative to `pages/` with no extension (`pages/blog/post.hrml` ->
+/// `"blog/post"`).
+fn discover_page_routes(pages_path: &Path) -> Result<Vec<String>, String> {
+    let mut routes = Vec::new();
+    collect_hrml_routes(pages_path, pages_path, &mut routes)
+        .map_err(|e| format!("Failed to walk '{}': {}", pages_path.display(), e))?;
+    routes.sort();
+    Ok(routes)
+}
+
+fn collect_hrml_routes(root: &Path, dir: &Path, routes: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        if entry_path.is_dir() {
+            collect_hrml_routes(root, &entry_path, routes)?;
+        } else if entry_path.extension().and_then(|ext| ext.to_str()) == Some("hrml") {
+            let relative = entry_path.strip_prefix(root).unwrap_or(&entry_path).with_extension("");
+            routes.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+    Ok(())
+}
+
+/// `"index"` -> `<out_dir>/index.html`; `"blog/index"` -> `<out_dir>/blog/index.html`;
+/// anything else -> `<out_dir>/<route>/index.html`, so built pages keep working
+/// extensionless URLs once served as static files.
+fn route_out_path(out_dir: &Path, route: &str) -> PathBuf {
+    if route == "index" || route.ends_with("/index") {
+        let dir = route.strip_suffix("index").unwrap_or("").trim_end_matches('/');
+        if dir.is_empty() {
+            out_dir.join("index.html")
+        } else {
+            out_dir.join(dir).join("index.html")
+        }
+    } else {
+        out_dir.join(route).join("index.html")
+    }
+}
+
+fn copy_dir_all(src: &Path, dst: &Path) -> io::Result<()> {
+    fs::create_dir_all(dst)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let entry_path = entry.path();
+        let dest_path = dst.join(entry.file_name());
+        if entry_path.is_dir() {
+            copy_dir_all(&entry_path, &dest_path)?;
+        } else {
+            fs::copy(&entry_path, &dest_path)?;
+        }
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct AppState {
     config: Arc<config::Config>,
-    template_engine: Arc<template::Engine>,
-    python_runtime: Arc<python::Runtime>,
+    // Held behind a lock so the dev-mode watcher can swap in a freshly
+    // rebuilt engine/runtime without restarting the server; `serve` mode
+    // just never writes to it.
+    template_engine: Arc<RwLock<Arc<template::Engine>>>,
+    python_runtime: Arc<RwLock<Arc<python::Runtime>>>,
+    // `db::Pool` is already `Arc`-backed internally, so this is held
+    // directly rather than wrapped again.
+    db_pool: pool::Pool,
+    routes: Arc<router::RouteTable>,
+    // Rebuilt alongside `template_engine` on a dev-mode reload, since
+    // static/ is one of the watched paths.
+    assets: Arc<RwLock<Arc<assets::AssetManifest>>>,
+    live_reload: Option<broadcast::Sender<String>>,
+    dev_mode: bool,
+}
+
+fn current_engine(state: &AppState) -> Arc<template::Engine> {
+    state.template_engine.read().unwrap().clone()
+}
+
+fn current_runtime(state: &AppState) -> Arc<python::Runtime> {
+    state.python_runtime.read().unwrap().clone()
 }
 
 async fn run_server(project_path: &Path, dev_mode: bool) {
@@ -417,53 +644,199 @@ async fn run_server(project_path: &Path, dev_mode: bool) {
         eprintln!("Error: Cannot access directory '{}': {}", project_path.display(), e);
         process::exit(1);
     }
-    
+
     // Validate project first
     if let Err(e) = validate_project(project_path) {
         eprintln!("Error: {}", e);
         process::exit(1);
     }
-    
+
     let config = config::Config::load("hrml.toml").unwrap_or_default();
-    
+
     if dev_mode {
         println!("Starting HRML development server on {}:{}", config.host, config.port);
         println!("   Watching for changes...");
     } else {
         println!("Starting HRML server on {}:{}", config.host, config.port);
     }
-    
-    let template_engine = Arc::new(
+
+    if !config.database.auto_create && !Path::new(&config.database.path).exists() {
+        eprintln!(
+            "Error: database '{}' does not exist and `[database] auto_create` is false",
+            config.database.path
+        );
+        process::exit(1);
+    }
+
+    db::init_settings(
+        &config.database.path,
+        config.database.pool_size,
+        config.database.busy_timeout_ms,
+        pool::JournalMode::parse(&config.database.journal_mode),
+        &config.migrations_path,
+    );
+    let db_pool = db::shared_pool();
+
+    let routes = Arc::new(router::RouteTable::load(Path::new(".")).unwrap_or_else(|e| {
+        eprintln!("[WARNING] Failed to load route table: {}", e);
+        router::RouteTable::default()
+    }));
+
+    let asset_manifest = assets::AssetManifest::build(Path::new(&config.static_path)).unwrap_or_else(|e| {
+        eprintln!("[WARNING] Failed to build asset manifest: {}", e);
+        assets::AssetManifest::default()
+    });
+    let assets = Arc::new(RwLock::new(Arc::new(asset_manifest.clone())));
+
+    let template_engine = Arc::new(RwLock::new(Arc::new(
         template::Engine::new(&config.templates_path)
             .with_site_name(config.site_name.clone())
             .with_description(config.site_description.clone())
             .with_favicon(config.favicon.clone())
-    );
-    let python_runtime = Arc::new(python::Runtime::new(&config.endpoints_path));
-    
+            .with_asset_manifest(asset_manifest)
+    )));
+    let python_runtime = Arc::new(RwLock::new(Arc::new(python::Runtime::new(&config.endpoints_path))));
+
+    // Only dev mode gets a live_reload sender; `serve` leaves it `None` so
+    // `/hrml/live` and the injected client snippet both stay dark in prod.
+    let (live_reload_tx, _) = broadcast::channel::<String>(16);
+    let live_reload = dev_mode.then(|| live_reload_tx.clone());
+
+    let watcher_handle = if dev_mode {
+        Some(spawn_dev_watcher(&config, template_engine.clone(), python_runtime.clone(), assets.clone(), live_reload_tx))
+    } else {
+        None
+    };
+
     let state = AppState {
         config: Arc::new(config.clone()),
         template_engine,
         python_runtime,
+        db_pool,
+        routes,
+        assets,
+        live_reload,
+        dev_mode,
     };
-    
+
     let app = Router::new()
         .route("/", get(index_handler))
         .route("/hrml.js", get(hrml_js_handler))
+        .route("/hrml/live", get(live_reload_handler))
         .route("/api/*path", get(api_get_handler).post(endpoint_handler).delete(endpoint_handler))
+        .route("/static/*path", get(static_asset_handler))
         .route("/*path", get(page_handler).post(endpoint_handler))
-        .nest_service("/static", ServeDir::new(&config.static_path))
         .with_state(state);
-    
+
     let listener = tokio::net::TcpListener::bind(format!("{}:{}", config.host, config.port))
         .await
         .unwrap();
-    
+
     println!("   Server running at http://{}:{}", config.host, config.port);
     println!();
     println!("Press Ctrl+C to stop");
-    
-    axum::serve(listener, app).await.unwrap();
+
+    let serve = axum::serve(listener, app);
+
+    // Keep the watcher task's lifetime tied to the server's: if either
+    // stops (server shutdown, or the watcher thread dying), bring both down
+    // together instead of leaking a detached background task.
+    match watcher_handle {
+        Some(watcher_handle) => {
+            tokio::select! {
+                result = serve => { result.unwrap(); }
+                _ = watcher_handle => {}
+            }
+        }
+        None => {
+            serve.await.unwrap();
+        }
+    }
+}
+
+/// Watches `templates_path`, `endpoints_path`, and `static_path` for
+/// changes and, after a short debounce, rebuilds the template engine and
+/// Python runtime and swaps them into `template_engine`/`python_runtime` so
+/// subsequent requests see the edit without a server restart.
+/// Each rebuild also broadcasts the changed path over `live_reload` for
+/// `/hrml/live` subscribers.
+fn spawn_dev_watcher(
+    config: &config::Config,
+    template_engine: Arc<RwLock<Arc<template::Engine>>>,
+    python_runtime: Arc<RwLock<Arc<python::Runtime>>>,
+    assets: Arc<RwLock<Arc<assets::AssetManifest>>>,
+    live_reload: broadcast::Sender<String>,
+) -> tokio::task::JoinHandle<()> {
+    let templates_path = config.templates_path.clone();
+    let endpoints_path = config.endpoints_path.clone();
+    let static_path = config.static_path.clone();
+    let site_name = config.site_name.clone();
+    let site_description = config.site_description.clone();
+    let favicon = config.favicon.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let mut watcher = match notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if let Ok(event) = event {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        }) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                eprintln!("[ERROR] Failed to start file watcher: {}", e);
+                return;
+            }
+        };
+
+        for watched in [&templates_path, &endpoints_path, &static_path] {
+            let watched_path = Path::new(watched);
+            if watched_path.exists() {
+                if let Err(e) = watcher.watch(watched_path, notify::RecursiveMode::Recursive) {
+                    eprintln!("[WARNING] Failed to watch '{}': {}", watched, e);
+                }
+            }
+        }
+
+        // A single save often fires several raw events; coalesce a burst
+        // into one rebuild, 300ms after the last event in the burst.
+        let debounce = Duration::from_millis(300);
+        loop {
+            let Ok(first_path) = rx.recv() else { break };
+            let mut last_path = first_path;
+            while let Ok(path) = rx.recv_timeout(debounce) {
+                last_path = path;
+            }
+
+            let new_manifest = assets::AssetManifest::build(Path::new(&static_path)).unwrap_or_else(|e| {
+                eprintln!("[WARNING] Failed to rebuild asset manifest: {}", e);
+                assets::AssetManifest::default()
+            });
+            let new_engine = template::Engine::new(&templates_path)
+                .with_site_name(site_name.clone())
+                .with_description(site_description.clone())
+                .with_favicon(favicon.clone())
+                .with_asset_manifest(new_manifest.clone());
+            *template_engine.write().unwrap() = Arc::new(new_engine);
+            *python_runtime.write().unwrap() = Arc::new(python::Runtime::new(&endpoints_path));
+            *assets.write().unwrap() = Arc::new(new_manifest);
+
+            println!("[RELOAD] Detected change: {}", last_path.display());
+            let _ = live_reload.send(last_path.display().to_string());
+        }
+    })
+}
+
+async fn live_reload_handler(State(state): State<AppState>) -> Response {
+    let Some(sender) = &state.live_reload else {
+        return (StatusCode::NOT_FOUND, "Live reload is only available in dev mode").into_response();
+    };
+
+    let stream = BroadcastStream::new(sender.subscribe())
+        .filter_map(|msg| async move { msg.ok().map(|path| Ok::<_, Infallible>(Event::default().data(path))) });
+
+    Sse::new(stream).into_response()
 }
 
 #[tokio::main]
@@ -516,11 +889,18 @@ async fn main() {
                 }
             }
         }
+        "lsp" => {
+            let path = args.get(2).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
+            let base_path = path.canonicalize().unwrap_or(path);
+            lsp::run_stdio(base_path).await;
+        }
         "build" => {
             let path = args.get(2).map(PathBuf::from).unwrap_or_else(|| PathBuf::from("."));
             println!("Building static site from '{}'...", path.display());
-            println!("Note: Build feature coming in next version!");
-            println!("For now, use 'hrml serve' to run the server.");
+            if let Err(e) = build_project(&path) {
+                eprintln!("Build failed: {}", e);
+                process::exit(1);
+            }
         }
         _ => {
             eprintln!("Unknown command: {}", command);
@@ -532,7 +912,7 @@ async fn main() {
 }
 
 async fn index_handler(State(state): State<AppState>) -> Response {
-    match state.template_engine.render("pages/index.hrml", &serde_json::json!({})) {
+    match current_engine(&state).render("pages/index.hrml", &serde_json::json!({})) {
         Ok(html) => Html(html).into_response(),
         Err(e) => {
             eprintln!("[ERROR] Template render failed for /: {}", e);
@@ -545,19 +925,93 @@ async fn page_handler(
     State(state): State<AppState>,
     AxumPath(path): AxumPath<String>,
 ) -> Response {
+    if let Some(matched) = state.routes.match_path(&path) {
+        return render_matched_route(&state, matched).await;
+    }
+
     let template_path = format!("pages/{}.hrml", path);
-    
-    match state.template_engine.render(&template_path, &serde_json::json!({})) {
+
+    match current_engine(&state).render(&template_path, &serde_json::json!({})) {
         Ok(html) => Html(html).into_response(),
         Err(_) => (StatusCode::NOT_FOUND, "Page not found").into_response(),
     }
 }
 
+/// Renders (or redirects for) an explicit route match: `:segment` captures
+/// become top-level string values in the render context, and `status`/
+/// `cache_control` override the response that implicit routing always
+/// defaults to (200, no cache header).
+async fn render_matched_route(state: &AppState, matched: router::RouteMatch<'_>) -> Response {
+    if let Some(redirect_to) = &matched.route.redirect {
+        let status = matched
+            .route
+            .status
+            .and_then(|code| StatusCode::from_u16(code).ok())
+            .unwrap_or(StatusCode::FOUND);
+        return (status, [(axum::http::header::LOCATION, redirect_to.clone())]).into_response();
+    }
+
+    let Some(template) = &matched.route.template else {
+        return (StatusCode::NOT_FOUND, "Route has neither a template nor a redirect").into_response();
+    };
+
+    let context: serde_json::Map<String, serde_json::Value> = matched
+        .params
+        .iter()
+        .map(|(key, value)| (key.clone(), serde_json::Value::String(value.clone())))
+        .collect();
+
+    match current_engine(state).render(template, &serde_json::Value::Object(context)) {
+        Ok(html) => {
+            let status = matched
+                .route
+                .status
+                .and_then(|code| StatusCode::from_u16(code).ok())
+                .unwrap_or(StatusCode::OK);
+            let mut response = (status, Html(html)).into_response();
+            if let Some(cache_control) = &matched.route.cache_control {
+                if let Ok(value) = axum::http::HeaderValue::from_str(cache_control) {
+                    response.headers_mut().insert(axum::http::header::CACHE_CONTROL, value);
+                }
+            }
+            response
+        }
+        Err(e) => {
+            eprintln!("[ERROR] Route '{}' failed to render '{}': {}", matched.route.pattern, template, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Template error: {}", e)).into_response()
+        }
+    }
+}
+
+/// Runs a Python endpoint call. `Python::with_gil` is correct either way,
+/// but on a free-threaded (no-GIL) interpreter concurrent `with_gil`
+/// entries from different OS threads actually run bytecode in parallel -
+/// so on that build the call is dispatched onto Tokio's blocking thread
+/// pool instead of running inline on the worker thread handling this
+/// request, letting concurrent requests execute their Python side
+/// simultaneously. A normal GIL build gains nothing from the extra thread
+/// hop (PyO3 already serializes correctly on it), so it just calls inline.
+async fn dispatch_endpoint<F>(runtime: Arc<python::Runtime>, call: F) -> Result<serde_json::Value, python::EndpointError>
+where
+    F: FnOnce(&python::Runtime) -> Result<serde_json::Value, python::EndpointError> + Send + 'static,
+{
+    if runtime.is_free_threaded() {
+        match tokio::task::spawn_blocking(move || call(&runtime)).await {
+            Ok(result) => result,
+            Err(e) => Err(python::EndpointError::Internal(format!("endpoint task panicked: {}", e))),
+        }
+    } else {
+        call(&runtime)
+    }
+}
+
 async fn api_get_handler(
     State(state): State<AppState>,
     AxumPath(path): AxumPath<String>,
 ) -> Response {
-    match state.python_runtime.call_endpoint(&format!("/api/{}", path), &serde_json::json!({})) {
+    let runtime = current_runtime(&state);
+    let call_path = path.clone();
+    match dispatch_endpoint(runtime, move |rt| rt.call_endpoint(&format!("/api/{}", call_path), &serde_json::json!({}))).await {
         Ok(result) => {
             if let Some(html) = result.as_str() {
                 Html(html.to_string()).into_response()
@@ -567,7 +1021,7 @@ async fn api_get_handler(
         }
         Err(e) => {
             eprintln!("[ERROR] API GET /api/{} failed: {}", path, e);
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Endpoint error: {}", e)).into_response()
+            endpoint_error_response(e)
         },
     }
 }
@@ -577,7 +1031,16 @@ async fn endpoint_handler(
     AxumPath(path): AxumPath<String>,
     request: Request<Body>,
 ) -> Response {
-    // Extract form data from request body
+    let method = request.method().to_string();
+    let query = parse_query_string(request.uri().query().unwrap_or(""));
+    let headers = headers_to_json(request.headers());
+    let content_type = request
+        .headers()
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("")
+        .to_string();
+
     let body_bytes = match axum::body::to_bytes(request.into_body(), usize::MAX).await {
         Ok(bytes) => bytes,
         Err(e) => {
@@ -585,29 +1048,28 @@ async fn endpoint_handler(
             return (StatusCode::BAD_REQUEST, "Failed to read request body").into_response();
         }
     };
-    
-    // Parse form data
-    let form_data = if !body_bytes.is_empty() {
-        let body_str = String::from_utf8_lossy(&body_bytes);
-        let mut data = serde_json::Map::new();
-        
-        // Parse URL-encoded form data
-        for pair in body_str.split('&') {
-            if let Some((key, value)) = pair.split_once('=') {
-                let decoded_value = urlencoding::decode(value).unwrap_or(std::borrow::Cow::Borrowed(value));
-                data.insert(key.to_string(), serde_json::Value::String(decoded_value.to_string()));
-            }
+
+    let body = match parse_request_body(&content_type, body_bytes).await {
+        Ok(body) => body,
+        Err(e) => {
+            eprintln!("[ERROR] POST /{} - failed to parse body: {}", path, e);
+            return (StatusCode::BAD_REQUEST, format!("Failed to parse request body: {}", e)).into_response();
         }
-        
-        serde_json::Value::Object(data)
-    } else {
-        serde_json::json!({})
     };
-    
+
     // Transform path to include /api/ prefix
     let full_path = format!("/api/{}", path);
-    
-    match state.python_runtime.call_endpoint(&full_path, &form_data) {
+
+    let req = python::EndpointRequest {
+        method,
+        path: full_path,
+        query,
+        body,
+        headers,
+    };
+
+    let runtime = current_runtime(&state);
+    match dispatch_endpoint(runtime, move |rt| rt.call_endpoint_full(&req)).await {
         Ok(result) => {
             if let Some(html) = result.as_str() {
                 Html(html.to_string()).into_response()
@@ -617,16 +1079,220 @@ async fn endpoint_handler(
         }
         Err(e) => {
             eprintln!("[ERROR] POST /{} - endpoint error: {}", path, e);
-            (StatusCode::INTERNAL_SERVER_ERROR, format!("Endpoint error: {}", e)).into_response()
+            endpoint_error_response(e)
         },
     }
 }
 
-async fn hrml_js_handler() -> Response {
+/// An `hrml.HttpError` raised from a handler carries the status/body the
+/// handler actually intended; anything else is an unexpected failure and
+/// stays a 500 (the detailed traceback already went to stderr above).
+fn endpoint_error_response(error: python::EndpointError) -> Response {
+    match error {
+        python::EndpointError::Http { status, body } => {
+            let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+            (status, serde_json::to_string(&body).unwrap_or_default()).into_response()
+        }
+        python::EndpointError::Internal(message) => {
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("Endpoint error: {}", message)).into_response()
+        }
+    }
+}
+
+/// Dispatches on `Content-Type` so JSON and multipart bodies reach Python as
+/// real structured values instead of being hand-split as if they were
+/// urlencoded, with the historical `&`/`=` split kept as the fallback for
+/// `application/x-www-form-urlencoded` (and anything unrecognized).
+async fn parse_request_body(content_type: &str, body_bytes: Bytes) -> Result<serde_json::Value, String> {
+    let mime = content_type.split(';').next().unwrap_or("").trim();
+
+    if mime.eq_ignore_ascii_case("application/json") {
+        if body_bytes.is_empty() {
+            return Ok(serde_json::json!({}));
+        }
+        return serde_json::from_slice(&body_bytes).map_err(|e| e.to_string());
+    }
+
+    if mime.eq_ignore_ascii_case("multipart/form-data") {
+        return parse_multipart_body(content_type, body_bytes).await;
+    }
+
+    Ok(parse_urlencoded_body(&body_bytes))
+}
+
+fn parse_urlencoded_body(body_bytes: &Bytes) -> serde_json::Value {
+    if body_bytes.is_empty() {
+        return serde_json::json!({});
+    }
+
+    let body_str = String::from_utf8_lossy(body_bytes);
+    let mut data = serde_json::Map::new();
+    for pair in body_str.split('&') {
+        if let Some((key, value)) = pair.split_once('=') {
+            let decoded_value = urlencoding::decode(value).unwrap_or(std::borrow::Cow::Borrowed(value));
+            data.insert(key.to_string(), serde_json::Value::String(decoded_value.to_string()));
+        }
+    }
+    serde_json::Value::Object(data)
+}
+
+/// Text fields become plain JSON strings; file fields become an object
+/// with `filename`, `content_type` and the bytes base64-encoded under
+/// `data_base64`.
+async fn parse_multipart_body(content_type: &str, body_bytes: Bytes) -> Result<serde_json::Value, String> {
+    let boundary = multer::parse_boundary(content_type).map_err(|e| e.to_string())?;
+    let stream = futures::stream::once(async move { Ok::<_, Infallible>(body_bytes) });
+    let mut multipart = multer::Multipart::new(stream, boundary);
+
+    let mut data = serde_json::Map::new();
+    while let Some(field) = multipart.next_field().await.map_err(|e| e.to_string())? {
+        let name = field.name().unwrap_or("").to_string();
+        let file_name = field.file_name().map(|s| s.to_string());
+        let field_content_type = field.content_type().map(|m| m.to_string());
+        let bytes = field.bytes().await.map_err(|e| e.to_string())?;
+
+        let value = match file_name {
+            Some(file_name) => {
+                use base64::Engine;
+                serde_json::json!({
+                    "filename": file_name,
+                    "content_type": field_content_type,
+                    "data_base64": base64::engine::general_purpose::STANDARD.encode(&bytes),
+                })
+            }
+            None => serde_json::Value::String(String::from_utf8_lossy(&bytes).to_string()),
+        };
+        data.insert(name, value);
+    }
+    Ok(serde_json::Value::Object(data))
+}
+
+fn parse_query_string(query: &str) -> serde_json::Value {
+    if query.is_empty() {
+        return serde_json::json!({});
+    }
+
+    let mut data = serde_json::Map::new();
+    for pair in query.split('&') {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let decoded_key = urlencoding::decode(key).unwrap_or(std::borrow::Cow::Borrowed(key));
+        let decoded_value = urlencoding::decode(value).unwrap_or(std::borrow::Cow::Borrowed(value));
+        data.insert(decoded_key.to_string(), serde_json::Value::String(decoded_value.to_string()));
+    }
+    serde_json::Value::Object(data)
+}
+
+fn headers_to_json(headers: &axum::http::HeaderMap) -> serde_json::Value {
+    let mut data = serde_json::Map::new();
+    for (name, value) in headers {
+        if let Ok(value) = value.to_str() {
+            data.insert(name.to_string(), serde_json::Value::String(value.to_string()));
+        }
+    }
+    serde_json::Value::Object(data)
+}
+
+async fn hrml_js_handler(State(state): State<AppState>) -> Response {
+    let body = if state.dev_mode {
+        format!("{}\n{}", HRML_JS, LIVE_RELOAD_CLIENT_JS)
+    } else {
+        HRML_JS.to_string()
+    };
+
     (
         StatusCode::OK,
         [("content-type", "application/javascript")],
-        HRML_JS,
+        body,
     )
         .into_response()
+}
+
+/// Serves `/static/*path`. Fingerprinted URLs (`css/style.a1b2c3d4.css`) are
+/// content-addressed, so they're safe to cache forever; anything else gets a
+/// short TTL plus an `ETag` so repeat requests can come back as a cheap `304`.
+async fn static_asset_handler(
+    State(state): State<AppState>,
+    AxumPath(requested): AxumPath<String>,
+    request: Request<Body>,
+) -> Response {
+    let manifest = state.assets.read().unwrap().clone();
+    let (logical, fingerprinted) = manifest.locate(&requested);
+
+    // `requested` comes from a percent-decoded wildcard segment, so `logical`
+    // can contain `..`/absolute-path tricks (`/static/..%2f..%2fetc/passwd`).
+    // Canonicalize and require the result stay under the static root instead
+    // of trusting it - this is what `ServeDir` gave us for free before this
+    // handler replaced it.
+    let static_root = match Path::new(&state.config.static_path).canonicalize() {
+        Ok(root) => root,
+        Err(_) => return (StatusCode::NOT_FOUND, "Not found").into_response(),
+    };
+    let file_path = match static_root.join(&logical).canonicalize() {
+        Ok(path) if path.starts_with(&static_root) => path,
+        _ => return (StatusCode::NOT_FOUND, "Not found").into_response(),
+    };
+    let bytes = match fs::read(&file_path) {
+        Ok(bytes) => bytes,
+        Err(_) => return (StatusCode::NOT_FOUND, "Not found").into_response(),
+    };
+
+    let content_type = guess_content_type(&logical);
+
+    if fingerprinted {
+        let mut response = (StatusCode::OK, bytes).into_response();
+        response.headers_mut().insert(axum::http::header::CONTENT_TYPE, content_type.parse().unwrap());
+        response.headers_mut().insert(
+            axum::http::header::CACHE_CONTROL,
+            "public, max-age=31536000, immutable".parse().unwrap(),
+        );
+        return response;
+    }
+
+    let etag = match manifest.hash_of(&logical) {
+        Some(hash) => format!("\"{}\"", hash),
+        None => format!("\"{:x}\"", fallback_hash(&bytes)),
+    };
+
+    if let Some(if_none_match) = request.headers().get(axum::http::header::IF_NONE_MATCH) {
+        if if_none_match.to_str().ok() == Some(etag.as_str()) {
+            return StatusCode::NOT_MODIFIED.into_response();
+        }
+    }
+
+    let mut response = (StatusCode::OK, bytes).into_response();
+    response.headers_mut().insert(axum::http::header::CONTENT_TYPE, content_type.parse().unwrap());
+    response.headers_mut().insert(axum::http::header::CACHE_CONTROL, "public, max-age=60".parse().unwrap());
+    response.headers_mut().insert(axum::http::header::ETAG, etag.parse().unwrap());
+    response
+}
+
+/// `static/` files outside the manifest (e.g. added after it was built)
+/// still need an `ETag`; re-hashing per request is fine since those are
+/// the rare, short-TTL case rather than the hot fingerprinted path.
+fn fallback_hash(bytes: &[u8]) -> u64 {
+    use std::hash::{Hash, Hasher};
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn guess_content_type(path: &str) -> &'static str {
+    match path.rsplit_once('.').map(|(_, ext)| ext.to_ascii_lowercase()).as_deref() {
+        Some("css") => "text/css",
+        Some("js") => "application/javascript",
+        Some("json") => "application/json",
+        Some("html") | Some("htm") => "text/html",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("ico") => "image/x-icon",
+        Some("woff") => "font/woff",
+        Some("woff2") => "font/woff2",
+        Some("txt") => "text/plain",
+        Some("xml") => "application/xml",
+        Some("webp") => "image/webp",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    }
 }
\ No newline at end of file
@@ -0,0 +1,105 @@
+/// Markdown-to-Element rendering pipeline: parses CommonMark and emits the
+/// crate's existing `Element` tree instead of raw strings, so Markdown
+/// content composes with `div()`, `section()`, etc. just like hand-built
+/// markup.
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Parser, Tag, TagEnd};
+
+use crate::html::{
+    a, blockquote, code, div, em, h1, h2, h3, h4, h5, h6, li, ol, p, strong, ul, Element,
+};
+
+/// Render `src` as a standalone `Element` (wrapped in a `div()`).
+pub fn markdown(src: &str) -> Element {
+    markdown_into(src, div())
+}
+
+/// Render `src` and attach its nodes as children of `root`, so callers can
+/// pick the wrapping tag (e.g. `markdown_into(body, section().class("post"))`).
+pub fn markdown_into(src: &str, root: Element) -> Element {
+    let mut stack: Vec<Element> = vec![root];
+
+    for event in Parser::new(src) {
+        match event {
+            Event::Start(tag) => stack.push(start_element(&tag)),
+            Event::End(tag_end) => {
+                let finished = stack.pop().unwrap_or_else(div).build();
+                let parent = stack.pop().unwrap_or_else(div);
+                stack.push(attach(parent, &tag_end, finished));
+            }
+            Event::Text(text) => {
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.text(&text));
+            }
+            Event::Code(text) => {
+                let inline_code = code().text(&text).build();
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.child(&inline_code));
+            }
+            // Inline/raw HTML passes through unescaped, as documented.
+            Event::Html(html) | Event::InlineHtml(html) => {
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.child(&html));
+            }
+            Event::SoftBreak => {
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.text(" "));
+            }
+            Event::HardBreak => {
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.child("<br>"));
+            }
+            Event::Rule => {
+                let top = stack.pop().unwrap_or_else(div);
+                stack.push(top.child("<hr>"));
+            }
+            _ => {}
+        }
+    }
+
+    stack.pop().unwrap_or_else(div)
+}
+
+fn start_element(tag: &Tag) -> Element {
+    match tag {
+        Tag::Paragraph => p(),
+        Tag::Heading { level, .. } => match level {
+            HeadingLevel::H1 => h1(),
+            HeadingLevel::H2 => h2(),
+            HeadingLevel::H3 => h3(),
+            HeadingLevel::H4 => h4(),
+            HeadingLevel::H5 => h5(),
+            HeadingLevel::H6 => h6(),
+        },
+        Tag::BlockQuote(_) => blockquote(),
+        Tag::CodeBlock(kind) => {
+            let code_el = code();
+            match kind {
+                CodeBlockKind::Fenced(lang) if !lang.is_empty() => {
+                    code_el.class(&format!("language-{}", lang))
+                }
+                _ => code_el,
+            }
+        }
+        Tag::List(None) => ul(),
+        Tag::List(Some(_)) => ol(),
+        Tag::Item => li(),
+        Tag::Emphasis => em(),
+        Tag::Strong => strong(),
+        Tag::Link { dest_url, title, .. } => {
+            let link = a().href(dest_url);
+            if title.is_empty() { link } else { link.attr("title", title) }
+        }
+        Tag::Image { dest_url, .. } => div().attr("data-image-src", dest_url),
+        _ => div(),
+    }
+}
+
+fn attach(parent: Element, tag_end: &TagEnd, built_child: String) -> Element {
+    match tag_end {
+        // Fenced/indented code blocks render as `<pre><code>...</code></pre>`;
+        // `built_child` here is already the serialized `<code>...</code>`.
+        TagEnd::CodeBlock => parent.child(&format!("<pre>{}</pre>", built_child)),
+        _ => parent.child(&built_child),
+    }
+}
@@ -0,0 +1,165 @@
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Migration {
+    pub version: i64,
+    pub name: String,
+    pub up: String,
+    #[serde(default)]
+    pub down: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MigrationsFile {
+    #[serde(rename = "migration", default)]
+    migrations: Vec<Migration>,
+}
+
+fn checksum(sql: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(sql.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Load migrations ordered by version from a `migrations.toml` file (or a
+/// `migrations/` directory containing one), or return an empty list if
+/// neither exists. A missing migrations source is not an error: most
+/// projects don't need one.
+pub fn load(migrations_path: &str) -> Result<Vec<Migration>, String> {
+    let path = Path::new(migrations_path);
+
+    let toml_path = if path.is_dir() {
+        path.join("migrations.toml")
+    } else {
+        path.to_path_buf()
+    };
+
+    if !toml_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&toml_path)
+        .map_err(|e| format!("Failed to read {}: {}", toml_path.display(), e))?;
+    let parsed: MigrationsFile = toml::from_str(&content)
+        .map_err(|e| format!("Failed to parse {}: {}", toml_path.display(), e))?;
+
+    let mut migrations = parsed.migrations;
+    migrations.sort_by_key(|m| m.version);
+    Ok(migrations)
+}
+
+/// Ensure the bookkeeping table exists, then apply every pending migration
+/// (version greater than the highest already-applied version) inside a
+/// single transaction, recording each new version with a checksum of its
+/// `up` SQL. If a previously-applied migration's SQL no longer matches its
+/// recorded checksum, refuse to start rather than silently drifting.
+pub fn apply_pending(conn: &mut Connection, migrations_path: &str) -> Result<(), String> {
+    let migrations = load(migrations_path)?;
+    if migrations.is_empty() {
+        return Ok(());
+    }
+
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS _hrml_migrations (
+            version INTEGER PRIMARY KEY,
+            name TEXT NOT NULL,
+            checksum TEXT NOT NULL,
+            applied_at TEXT NOT NULL DEFAULT (datetime('now'))
+        )",
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Verify already-applied migrations haven't been edited after the fact.
+    {
+        let mut stmt = conn
+            .prepare("SELECT version, checksum FROM _hrml_migrations")
+            .map_err(|e| e.to_string())?;
+        let applied: Vec<(i64, String)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.to_string())?;
+
+        for (version, recorded_checksum) in &applied {
+            if let Some(migration) = migrations.iter().find(|m| m.version == *version) {
+                if &checksum(&migration.up) != recorded_checksum {
+                    return Err(format!(
+                        "Migration {} ({}) has been edited after being applied; refusing to start",
+                        version, migration.name
+                    ));
+                }
+            }
+        }
+    }
+
+    let max_applied: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM _hrml_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let pending: Vec<&Migration> = migrations
+        .iter()
+        .filter(|m| m.version > max_applied)
+        .collect();
+
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for migration in &pending {
+        tx.execute_batch(&migration.up)
+            .map_err(|e| format!("Migration {} ({}) failed: {}", migration.version, migration.name, e))?;
+        tx.execute(
+            "INSERT INTO _hrml_migrations (version, name, checksum) VALUES (?1, ?2, ?3)",
+            rusqlite::params![migration.version, migration.name, checksum(&migration.up)],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Like `apply_pending`, but read-only: reports which migrations would run
+/// without executing any SQL, so `hrml check` can surface drift without
+/// mutating the database.
+pub fn pending(conn: &Connection, migrations_path: &str) -> Result<Vec<Migration>, String> {
+    let migrations = load(migrations_path)?;
+    if migrations.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let bookkeeping_exists = conn
+        .query_row(
+            "SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_hrml_migrations'",
+            [],
+            |_| Ok(()),
+        )
+        .optional()
+        .map_err(|e| e.to_string())?
+        .is_some();
+
+    let max_applied: i64 = if bookkeeping_exists {
+        conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM _hrml_migrations",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?
+    } else {
+        0
+    };
+
+    Ok(migrations
+        .into_iter()
+        .filter(|m| m.version > max_applied)
+        .collect())
+}
@@ -0,0 +1,73 @@
+use r2d2_sqlite::SqliteConnectionManager;
+use rusqlite::Connection;
+
+pub type Pool = r2d2::Pool<SqliteConnectionManager>;
+pub type PooledConnection = r2d2::PooledConnection<SqliteConnectionManager>;
+
+/// Journal mode applied to every pooled connection when it is opened. `Wal` lets
+/// readers run concurrently with a single writer and is the recommended
+/// default for a server process; `Delete` is SQLite's historical default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JournalMode {
+    Delete,
+    Wal,
+}
+
+impl JournalMode {
+    pub fn parse(s: &str) -> Self {
+        match s.to_ascii_lowercase().as_str() {
+            "wal" => JournalMode::Wal,
+            _ => JournalMode::Delete,
+        }
+    }
+
+    fn pragma_value(self) -> &'static str {
+        match self {
+            JournalMode::Delete => "DELETE",
+            JournalMode::Wal => "WAL",
+        }
+    }
+}
+
+/// Per-connection settings applied once, when the pool opens a connection
+/// (r2d2 calls `on_acquire` on creation, not on each checkout). The pragmas
+/// persist for the connection's lifetime, so every checked-out connection
+/// carries them.
+#[derive(Debug, Clone, Copy)]
+pub struct ConnectionOptions {
+    pub enable_foreign_keys: bool,
+    pub busy_timeout_ms: u32,
+    pub journal_mode: JournalMode,
+}
+
+impl Default for ConnectionOptions {
+    fn default() -> Self {
+        Self {
+            enable_foreign_keys: true,
+            busy_timeout_ms: 5_000,
+            journal_mode: JournalMode::Wal,
+        }
+    }
+}
+
+impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnectionOptions {
+    fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
+        if self.enable_foreign_keys {
+            conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        }
+        conn.execute_batch(&format!("PRAGMA busy_timeout = {};", self.busy_timeout_ms))?;
+        conn.pragma_update(None, "journal_mode", self.journal_mode.pragma_value())?;
+        Ok(())
+    }
+}
+
+/// Build a connection pool against `path`, applying `options` to every
+/// connection the pool opens and capping the pool at `max_size` connections.
+pub fn build(path: &str, max_size: u32, options: ConnectionOptions) -> Result<Pool, String> {
+    let manager = SqliteConnectionManager::file(path);
+    r2d2::Pool::builder()
+        .max_size(max_size)
+        .connection_customizer(Box::new(options))
+        .build(manager)
+        .map_err(|e| format!("Failed to build connection pool for {}: {}", path, e))
+}
@@ -1,12 +1,339 @@
+use pyo3::create_exception;
 use pyo3::prelude::*;
-use pyo3::types::{PyDict, PyModule};
+use pyo3::types::PyModule;
+use pyo3::wrap_pyfunction;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
 use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::SystemTime;
 
 const HRML_PYTHON_LIB: &str = include_str!("runtime/hrml.py");
+const MEMORY_IMPORTER_PY: &str = include_str!("runtime/meta_importer.py");
+
+// Raised from a handler as `hrml.HttpError(status, body)` (or one of the
+// subclasses below) to produce a specific HTTP response instead of always
+// falling through to a 500; `call_python_function` downcasts on these
+// before treating an exception as an unexpected failure.
+create_exception!(hrml, HttpError, pyo3::exceptions::PyException);
+create_exception!(hrml, NotFound, HttpError);
+create_exception!(hrml, Validation, HttpError);
+create_exception!(hrml, Unauthorized, HttpError);
+
+/// What a handler call failed with, once a `PyErr` has been translated:
+/// either an intentional `hrml.HttpError` (status + body exactly as raised)
+/// or anything else, captured with its full traceback so a 500 is at least
+/// debuggable from the server log.
+#[derive(Debug)]
+pub enum EndpointError {
+    Http { status: u16, body: Value },
+    Internal(String),
+}
+
+impl fmt::Display for EndpointError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EndpointError::Http { status, body } => write!(f, "HTTP {}: {}", status, body),
+            EndpointError::Internal(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// What a handler actually receives as its single argument. Replaces the
+/// `PyDict` `call_python_function` used to build by hand: the fields are
+/// typed and documented by the class itself instead of by convention, and
+/// `__getitem__` still answers `req["id"]`-style lookups so handlers written
+/// against the old dict shape don't need to change.
+#[pyo3::pyclass]
+pub struct PyRequest {
+    #[pyo3(get)]
+    id: String,
+    #[pyo3(get)]
+    action: String,
+    #[pyo3(get)]
+    method: String,
+    #[pyo3(get)]
+    path: String,
+    query_json: String,
+    body_json: String,
+    headers_json: String,
+}
+
+#[pyo3::pymethods]
+impl PyRequest {
+    #[getter]
+    fn query(&self, py: Python) -> PyResult<PyObject> {
+        Self::parse(py, &self.query_json)
+    }
+
+    #[getter]
+    fn body(&self, py: Python) -> PyResult<PyObject> {
+        Self::parse(py, &self.body_json)
+    }
+
+    /// Alias for `body`, kept for handlers written before `body`/`headers`
+    /// existed and `data` was the only way to read the request payload.
+    #[getter]
+    fn data(&self, py: Python) -> PyResult<PyObject> {
+        Self::parse(py, &self.body_json)
+    }
+
+    #[getter]
+    fn headers(&self, py: Python) -> PyResult<PyObject> {
+        Self::parse(py, &self.headers_json)
+    }
+
+    fn __getitem__(&self, py: Python, key: String) -> PyResult<PyObject> {
+        match key.as_str() {
+            "id" => Ok(self.id.clone().into_py(py)),
+            "action" => Ok(self.action.clone().into_py(py)),
+            "method" => Ok(self.method.clone().into_py(py)),
+            "path" => Ok(self.path.clone().into_py(py)),
+            "query" => self.query(py),
+            "body" => self.body(py),
+            "data" => self.data(py),
+            "headers" => self.headers(py),
+            other => Err(pyo3::exceptions::PyKeyError::new_err(other.to_string())),
+        }
+    }
+}
+
+impl PyRequest {
+    fn parse(py: Python, json: &str) -> PyResult<PyObject> {
+        Ok(py.import("json")?.call_method1("loads", (json,))?.into_py(py))
+    }
+}
+
+/// A handle onto one table, returned by `db.table(name)`. Methods mirror the
+/// free-function CRUD surface the ad-hoc `db` module used to expose, just
+/// attached to a typed object instead of repeating the table name on every
+/// call.
+#[pyo3::pyclass]
+pub struct PyTable {
+    name: String,
+}
+
+#[pyo3::pymethods]
+impl PyTable {
+    #[new]
+    fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    fn create(&self, schema: String) -> PyResult<()> {
+        crate::db::table(&self.name)
+            .create(&schema)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    fn insert(&self, data: String) -> PyResult<i64> {
+        let value: Value = serde_json::from_str(&data)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        crate::db::table(&self.name)
+            .insert(value)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    fn find(&self, id: i64) -> PyResult<String> {
+        let result = crate::db::table(&self.name)
+            .find(id)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+        Ok(serde_json::to_string(&result).unwrap_or_default())
+    }
+
+    fn find_all(&self) -> PyResult<String> {
+        let results = crate::db::table(&self.name)
+            .find_all()
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+        Ok(serde_json::to_string(&results).unwrap_or_default())
+    }
+
+    fn update(&self, id: i64, data: String) -> PyResult<usize> {
+        let value: Value = serde_json::from_str(&data)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        crate::db::table(&self.name)
+            .update(id, value)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    fn delete(&self, id: i64) -> PyResult<usize> {
+        crate::db::table(&self.name)
+            .delete(id)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    /// `filters` is `[{"field": ..., "op": "eq"|"neq"|"gt"|"lt"|"gte"|"lte"|"like"|"in", "value": ...}, ...]`.
+    #[pyo3(name = "where")]
+    fn where_(&self, filters: String) -> PyResult<String> {
+        let filters: Value = serde_json::from_str(&filters)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let rows = crate::db::table(&self.name)
+            .filtered(&filters)
+            .and_then(|q| q.all())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+        Ok(serde_json::to_string(&rows).unwrap_or_default())
+    }
+
+    fn count(&self, filters: String) -> PyResult<i64> {
+        let filters: Value = serde_json::from_str(&filters)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        crate::db::table(&self.name)
+            .filtered(&filters)
+            .and_then(|q| q.count())
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    fn insert_many(&self, rows: String) -> PyResult<Vec<i64>> {
+        let rows: Vec<Value> = serde_json::from_str(&rows)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        crate::db::table(&self.name)
+            .insert_many(rows)
+            .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+}
+
+#[pyo3::pyfunction]
+fn table(name: String) -> PyTable {
+    PyTable { name }
+}
+
+#[pyo3::pyfunction]
+fn query(sql: String, params: String) -> PyResult<String> {
+    let params: Vec<Value> = serde_json::from_str(&params)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    let rows = crate::db::query(&sql, params).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+    Ok(serde_json::to_string(&rows).unwrap_or_default())
+}
+
+#[pyo3::pyfunction]
+fn execute(sql: String, params: String) -> PyResult<usize> {
+    let params: Vec<Value> = serde_json::from_str(&params)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+    crate::db::execute(&sql, params).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+}
+
+#[pyo3::pyfunction]
+fn transaction() -> PyResult<PyTransaction> {
+    let inner = crate::db::ActiveTransaction::begin().map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+    Ok(PyTransaction { inner: Some(inner) })
+}
+
+/// The declarative half of the embedded runtime: everything backed by real
+/// Rust types (`Table`, `Request`, the `HttpError` hierarchy, and the `db`
+/// submodule) rather than by the hand-written Python source in
+/// `runtime/hrml.py`. `Runtime::new` calls this function directly against
+/// the module object it built from that embedded source, so `hrml` ends up
+/// with both the Python-level helpers and these native classes attached. The
+/// same function is also a valid `#[pymodule]` entry point, so `maturin
+/// build` can turn it into a standalone `hrml.so`/`hrml.pyd` - endpoint
+/// authors can then `import hrml` under plain CPython and unit-test
+/// handlers without a running server, getting identical `db`/`HttpError`
+/// behavior either way.
+#[pyo3::pymodule]
+fn hrml(py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add("HttpError", py.get_type::<HttpError>())?;
+    m.add("NotFound", py.get_type::<NotFound>())?;
+    m.add("Validation", py.get_type::<Validation>())?;
+    m.add("Unauthorized", py.get_type::<Unauthorized>())?;
+    m.add_class::<PyRequest>()?;
+
+    let db_module = PyModule::new(py, "db")?;
+    db_module.add_class::<PyTable>()?;
+    db_module.add_class::<PyTransaction>()?;
+    db_module.add_function(wrap_pyfunction!(table, db_module)?)?;
+    db_module.add_function(wrap_pyfunction!(query, db_module)?)?;
+    db_module.add_function(wrap_pyfunction!(execute, db_module)?)?;
+    db_module.add_function(wrap_pyfunction!(transaction, db_module)?)?;
+    m.add_submodule(db_module)?;
+
+    // `add_submodule` only attaches `db` as an attribute of `hrml` - it
+    // doesn't register it in `sys.modules`, which `from hrml.db import ...`
+    // needs. `db` (unqualified) is kept as an alias so handlers written
+    // against the old flat `import db` convention keep working.
+    let sys_modules = py.import("sys")?.getattr("modules")?;
+    sys_modules.set_item("hrml.db", db_module)?;
+    sys_modules.set_item("db", db_module)?;
+
+    Ok(())
+}
+
+/// `db.transaction()`'s Python handle: a context manager so handlers can
+/// write `with db.transaction() as tx: tx.execute(...)` and have it commit
+/// on a clean exit or roll back if the `with` block raises. `inner` is
+/// `None` once `__exit__` has run, so a handler holding onto a stale
+/// reference gets a clear error instead of silently reusing a closed
+/// transaction.
+#[pyo3::pyclass]
+struct PyTransaction {
+    inner: Option<crate::db::ActiveTransaction>,
+}
+
+#[pyo3::pymethods]
+impl PyTransaction {
+    fn __enter__(slf: PyRefMut<Self>) -> PyRefMut<Self> {
+        slf
+    }
+
+    fn __exit__(
+        &mut self,
+        exc_type: &pyo3::types::PyAny,
+        _exc_value: &pyo3::types::PyAny,
+        _traceback: &pyo3::types::PyAny,
+    ) -> PyResult<bool> {
+        if let Some(mut tx) = self.inner.take() {
+            if exc_type.is_none() {
+                tx.commit().map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+            } else {
+                let _ = tx.rollback();
+            }
+        }
+        Ok(false)
+    }
+
+    fn execute(&self, sql: String, params: String) -> PyResult<usize> {
+        let params: Vec<Value> = serde_json::from_str(&params)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let tx = self
+            .inner
+            .as_ref()
+            .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Transaction is already closed"))?;
+        tx.execute(&sql, params).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    }
+
+    fn query(&self, sql: String, params: String) -> PyResult<String> {
+        let params: Vec<Value> = serde_json::from_str(&params)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
+        let tx = self
+            .inner
+            .as_ref()
+            .ok_or_else(|| pyo3::exceptions::PyRuntimeError::new_err("Transaction is already closed"))?;
+        let rows = tx.query(&sql, params).map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
+        Ok(serde_json::to_string(&rows).unwrap_or_default())
+    }
+}
+
+/// A handler module imported once and reused across requests, plus the
+/// source mtime it was imported at so `get_module` knows when to re-import
+/// instead of serving a stale handler during dev iteration.
+struct CachedModule {
+    module: Py<PyModule>,
+    mtime: Option<SystemTime>,
+}
 
 pub struct Runtime {
     endpoints_path: PathBuf,
+    modules: Mutex<HashMap<String, CachedModule>>,
+    // Whether the embedded CPython was built with `Py_GIL_DISABLED` (the
+    // no-GIL interpreters). `Python::with_gil` is the correct entry point
+    // either way, but `main.rs`'s `dispatch_endpoint` reads this (via
+    // `is_free_threaded`) to decide whether to hop a call onto Tokio's
+    // blocking thread pool - on a free-threaded build that lets concurrent
+    // requests' Python code actually run in parallel instead of queuing
+    // behind one interpreter lock; on a normal GIL build the hop would just
+    // add overhead for no benefit, so calls stay inline.
+    free_threaded: bool,
 }
 
 impl Runtime {
@@ -14,7 +341,7 @@ impl Runtime {
         let endpoints_path = PathBuf::from(endpoints_path);
 
         // Initialize Python with embedded hrml module and database bindings
-        Python::with_gil(|py| {
+        let free_threaded = Python::with_gil(|py| {
             let sys = py.import("sys").expect("Failed to import sys");
             let sys_path = sys.getattr("path").expect("Failed to get sys.path");
 
@@ -25,6 +352,16 @@ impl Runtime {
             match PyModule::from_code(py, HRML_PYTHON_LIB, "hrml.py", "hrml") {
                 Ok(module) => {
                     eprintln!("[DEBUG] Successfully created hrml module from embedded code");
+
+                    // Populate the same module object with the compiled
+                    // extension surface (Table/Request/exception classes,
+                    // the `db` submodule) - this is the function `maturin
+                    // build` would also invoke as the module's real entry
+                    // point.
+                    if let Err(e) = hrml(py, module) {
+                        eprintln!("[WARNING] Failed to populate native hrml extension surface: {}", e);
+                    }
+
                     // Register in sys.modules to make it available for import
                     if let Ok(sys) = py.import("sys") {
                         if let Ok(sys_modules) = sys.getattr("modules") {
@@ -44,9 +381,14 @@ impl Runtime {
                 }
             }
 
-            // Create db module with database functions
-            if let Err(e) = Self::create_db_module(py) {
-                eprintln!("[WARNING] Failed to create db module: {}", e);
+            // Install the in-memory meta path finder and bake in everything
+            // under `endpoints/` as source strings, so `endpoints.*` imports
+            // resolve from memory rather than re-reading the filesystem on
+            // every request. The sys.path insertion below stays as a
+            // fallback for anything not baked in (e.g. handler modules added
+            // after startup in dev mode).
+            if let Err(e) = Self::install_memory_importer(py, &endpoints_path) {
+                eprintln!("[WARNING] Failed to install in-memory meta path importer: {}", e);
             }
 
             // Now add project directory to Python path for 'endpoints' module
@@ -73,94 +415,107 @@ impl Runtime {
                     .unwrap_or_default()
                     .get(0..3.min(sys_path.len().unwrap_or(0)))
             );
+
+            let free_threaded = py
+                .import("sysconfig")
+                .and_then(|sysconfig| sysconfig.call_method1("get_config_var", ("Py_GIL_DISABLED",)))
+                .ok()
+                .and_then(|value| value.extract::<i32>().ok())
+                .map(|value| value != 0)
+                .unwrap_or(false);
+            if free_threaded {
+                eprintln!("[INFO] Embedded CPython is a free-threaded (no-GIL) build");
+            }
+            free_threaded
         });
 
-        Self { endpoints_path }
+        Self {
+            endpoints_path,
+            modules: Mutex::new(HashMap::new()),
+            free_threaded,
+        }
     }
 
-    fn create_db_module(py: Python) -> PyResult<()> {
-        use crate::db;
-        use pyo3::wrap_pyfunction;
-
-        let db_module = PyModule::new(py, "db")?;
+    pub fn is_free_threaded(&self) -> bool {
+        self.free_threaded
+    }
 
-        #[pyo3::pyfunction]
-        fn table_create(name: String, schema: String) -> PyResult<()> {
-            db::table(&name)
-                .create(&schema)
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
-        }
+    /// Loads the meta path finder from embedded source and registers `hrml`
+    /// plus every module found under `endpoints_path` with it, so imports of
+    /// those names resolve from in-process buffers instead of disk.
+    fn install_memory_importer(py: Python, endpoints_path: &PathBuf) -> PyResult<()> {
+        let importer = PyModule::from_code(py, MEMORY_IMPORTER_PY, "meta_importer.py", "hrml._meta_importer")?;
 
-        #[pyo3::pyfunction]
-        fn table_insert(name: String, data: String) -> PyResult<i64> {
-            let value: Value = serde_json::from_str(&data)
-                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
-            db::table(&name)
-                .insert(value)
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
-        }
+        importer.call_method1("register", ("hrml", HRML_PYTHON_LIB, false))?;
 
-        #[pyo3::pyfunction]
-        fn table_find(name: String, id: i64) -> PyResult<String> {
-            let result = db::table(&name)
-                .find(id)
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
-            Ok(serde_json::to_string(&result).unwrap_or_default())
+        for (name, source, is_package) in Self::collect_endpoint_modules(endpoints_path) {
+            importer.call_method1("register", (name, source, is_package))?;
         }
 
-        #[pyo3::pyfunction]
-        fn table_find_all(name: String) -> PyResult<String> {
-            let results = db::table(&name)
-                .find_all()
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))?;
-            Ok(serde_json::to_string(&results).unwrap_or_default())
-        }
+        Ok(())
+    }
 
-        #[pyo3::pyfunction]
-        fn table_update(name: String, id: i64, data: String) -> PyResult<usize> {
-            let value: Value = serde_json::from_str(&data)
-                .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))?;
-            db::table(&name)
-                .update(id, value)
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    /// Walks `endpoints_path`, turning it into `(dotted_name, source,
+    /// is_package)` triples under the `endpoints` root - directories become
+    /// packages (using their `__init__.py` if present, else empty source),
+    /// `.py` files become submodules.
+    fn collect_endpoint_modules(endpoints_path: &PathBuf) -> Vec<(String, String, bool)> {
+        let mut modules = Vec::new();
+        if endpoints_path.exists() {
+            Self::collect_endpoint_modules_into(endpoints_path, "endpoints", &mut modules);
         }
+        modules
+    }
 
-        #[pyo3::pyfunction]
-        fn table_delete(name: String, id: i64) -> PyResult<usize> {
-            db::table(&name)
-                .delete(id)
-                .map_err(|e| pyo3::exceptions::PyRuntimeError::new_err(e))
+    fn collect_endpoint_modules_into(dir: &std::path::Path, dotted_prefix: &str, out: &mut Vec<(String, String, bool)>) {
+        let init_source = fs::read_to_string(dir.join("__init__.py")).unwrap_or_default();
+        out.push((dotted_prefix.to_string(), init_source, true));
+
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+                Self::collect_endpoint_modules_into(&path, &format!("{}.{}", dotted_prefix, name), out);
+            } else if path.extension().and_then(|ext| ext.to_str()) == Some("py")
+                && path.file_stem().and_then(|stem| stem.to_str()) != Some("__init__")
+            {
+                if let (Some(stem), Ok(source)) = (path.file_stem().and_then(|s| s.to_str()), fs::read_to_string(&path)) {
+                    out.push((format!("{}.{}", dotted_prefix, stem), source, false));
+                }
+            }
         }
+    }
 
-        db_module.add_function(wrap_pyfunction!(table_create, db_module)?)?;
-        db_module.add_function(wrap_pyfunction!(table_insert, db_module)?)?;
-        db_module.add_function(wrap_pyfunction!(table_find, db_module)?)?;
-        db_module.add_function(wrap_pyfunction!(table_find_all, db_module)?)?;
-        db_module.add_function(wrap_pyfunction!(table_update, db_module)?)?;
-        db_module.add_function(wrap_pyfunction!(table_delete, db_module)?)?;
-
-        py.import("sys")?
-            .getattr("modules")?
-            .set_item("db", db_module)?;
-
-        Ok(())
+    pub fn call_endpoint(&self, path: &str, data: &Value) -> Result<Value, EndpointError> {
+        self.call_endpoint_full(&EndpointRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: Value::Object(Default::default()),
+            body: data.clone(),
+            headers: Value::Object(Default::default()),
+        })
     }
 
-    pub fn call_endpoint(&self, path: &str, data: &Value) -> Result<Value, String> {
+    /// Same routing as `call_endpoint`, but carries the full request shape
+    /// (method, query string, headers) through to the Python handler
+    /// instead of just the body - used by `endpoint_handler`, which has all
+    /// of that available from the incoming HTTP request.
+    pub fn call_endpoint_full(&self, req: &EndpointRequest) -> Result<Value, EndpointError> {
         Python::with_gil(|py| {
             // Parse path to module and function
             // Path format: /api/module/id/action or /api/module/action
             eprintln!("[DEBUG] =========================================");
-            eprintln!("[DEBUG] Python call_endpoint with path: '{}'", path);
-            let parts: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+            eprintln!("[DEBUG] Python call_endpoint with path: '{}'", req.path);
+            let parts: Vec<&str> = req.path.trim_start_matches('/').split('/').collect();
             eprintln!("[DEBUG] Parts after split: {:?}", parts);
             eprintln!("[DEBUG] Parts length: {}", parts.len());
 
             if parts.len() < 2 {
-                return Err(
+                return Err(EndpointError::Internal(
                     "Invalid endpoint path - expected /api/module or /api/module/action"
                         .to_string(),
-                );
+                ));
             }
 
             // Use first 2 parts for module path (api/todos -> endpoints.api.todos)
@@ -188,16 +543,125 @@ impl Runtime {
 
             let function_name = "handler";
 
-            match self.call_python_function(py, &module_path, function_name, id, action, data) {
+            match self.call_python_function(py, &module_path, function_name, id, action, req) {
                 Ok(result) => Ok(result),
-                Err(e) => Err(format!(
-                    "Python error: {} (module_path: {})",
-                    e, module_path
-                )),
+                Err(e) => Err(Self::translate_error(py, e, &module_path)),
             }
         })
     }
 
+    /// An `hrml.HttpError` (or subclass) becomes a structured `{status,
+    /// body}` the HTTP layer can use verbatim; anything else is an
+    /// unexpected failure, reported with its full traceback instead of just
+    /// `str(e)` so a 500 is at least debuggable from the server log.
+    fn translate_error(py: Python, err: PyErr, module_path: &str) -> EndpointError {
+        if err.is_instance_of::<HttpError>(py) {
+            // Subclasses carry an implied status so `raise hrml.NotFound("x")`
+            // works without spelling out the code; a bare `HttpError` has no
+            // default and must supply one explicitly as `args[0]`.
+            let default_status = if err.is_instance_of::<NotFound>(py) {
+                Some(404)
+            } else if err.is_instance_of::<Unauthorized>(py) {
+                Some(401)
+            } else if err.is_instance_of::<Validation>(py) {
+                Some(422)
+            } else {
+                None
+            };
+            if let Some(status_body) = Self::extract_http_error(py, &err, default_status) {
+                return status_body;
+            }
+        }
+
+        let traceback = py
+            .import("traceback")
+            .and_then(|tb| tb.call_method1("format_exception", (err.get_type(py), err.value(py), err.traceback(py))))
+            .and_then(|lines| lines.extract::<Vec<String>>())
+            .map(|lines| lines.concat())
+            .unwrap_or_else(|_| err.to_string());
+
+        EndpointError::Internal(format!("{} (module_path: {})", traceback, module_path))
+    }
+
+    /// `hrml.HttpError(status, body)` stores `status`/`body` as the
+    /// exception's positional `args`, so they're read back the same way. A
+    /// subclass with a `default_status` can instead be raised as just
+    /// `hrml.NotFound(body)` - `args[0]` is tried as the status first, and
+    /// only treated as the body if that extraction fails and a default is
+    /// available.
+    fn extract_http_error(py: Python, err: &PyErr, default_status: Option<u16>) -> Option<EndpointError> {
+        let args = err.value(py).getattr("args").ok()?;
+        let first = args.get_item(0).ok();
+        let (status, body_index) = match first.as_ref().and_then(|v| v.extract::<u16>().ok()) {
+            Some(status) => (status, 1),
+            None => (default_status?, 0),
+        };
+        let body = match args.get_item(body_index).ok() {
+            Some(obj) => {
+                let json_str: String = py.import("json").ok()?.call_method1("dumps", (obj,)).ok()?.extract().ok()?;
+                serde_json::from_str(&json_str).unwrap_or_else(|_| serde_json::json!({}))
+            }
+            None => serde_json::json!({}),
+        };
+        Some(EndpointError::Http { status, body })
+    }
+
+    /// `endpoints.api.todos` -> `<endpoints_path>/api/todos.py`, the file
+    /// whose mtime decides whether `get_module` can reuse the cached import.
+    fn source_path(&self, module_path: &str) -> Option<PathBuf> {
+        let rest = module_path.strip_prefix("endpoints.")?;
+        let mut segments: Vec<&str> = rest.split('.').collect();
+        let last = segments.pop()?;
+        let mut path = self.endpoints_path.clone();
+        for segment in segments {
+            path.push(segment);
+        }
+        path.push(format!("{}.py", last));
+        Some(path)
+    }
+
+    fn source_mtime(&self, module_path: &str) -> Option<SystemTime> {
+        self.source_path(module_path)
+            .and_then(|path| fs::metadata(path).ok())
+            .and_then(|meta| meta.modified().ok())
+    }
+
+    /// Imports `module_path` once and reuses the cached `Py<PyModule>` on
+    /// later calls, re-importing only when the source file's mtime has
+    /// moved on since the cached copy was loaded - so dev-mode edits to
+    /// `endpoints/` still take effect without a server restart, but a busy
+    /// production server isn't paying importlib's cost on every request.
+    fn get_module(&self, py: Python, module_path: &str) -> PyResult<Py<PyModule>> {
+        let current_mtime = self.source_mtime(module_path);
+
+        let mut cache = self.modules.lock().unwrap();
+        if let Some(cached) = cache.get(module_path) {
+            if cached.mtime == current_mtime {
+                return Ok(cached.module.clone_ref(py));
+            }
+        }
+
+        let already_loaded = cache.contains_key(module_path);
+        let module = PyModule::import(py, module_path)?;
+        let module = if already_loaded {
+            // `import` hands back the stale object already sitting in
+            // `sys.modules`; `reload` is what actually re-runs the file.
+            py.import("importlib")?
+                .call_method1("reload", (module,))?
+                .downcast::<PyModule>()
+                .map_err(PyErr::from)?
+        } else {
+            module
+        };
+
+        let module: Py<PyModule> = module.into();
+        cache.insert(
+            module_path.to_string(),
+            CachedModule { module: module.clone_ref(py), mtime: current_mtime },
+        );
+        Ok(module)
+    }
+
     fn call_python_function(
         &self,
         py: Python,
@@ -205,25 +669,40 @@ impl Runtime {
         func_name: &str,
         id: &str,
         action: &str,
-        data: &Value,
+        req: &EndpointRequest,
     ) -> PyResult<Value> {
         eprintln!("Attempting to import module: {}", module_path);
-        let module = PyModule::import(py, module_path)?;
+        let module = self.get_module(py, module_path)?;
+        let module = module.as_ref(py);
         let func = module.getattr(func_name)?;
 
-        let req_dict = PyDict::new(py);
-        req_dict.set_item("id", id)?;
-        req_dict.set_item("action", action)?;
-
-        // Convert JSON Value to Python dict
-        let data_str = data.to_string();
-        let json_module = py.import("json")?;
-        let py_data = json_module.call_method1("loads", (data_str,))?;
-        req_dict.set_item("data", py_data)?;
-
-        let result = func.call1((req_dict,))?;
+        let py_request = Py::new(
+            py,
+            PyRequest {
+                id: id.to_string(),
+                action: action.to_string(),
+                method: req.method.clone(),
+                path: req.path.clone(),
+                query_json: req.query.to_string(),
+                body_json: req.body.to_string(),
+                headers_json: req.headers.to_string(),
+            },
+        )?;
+
+        let result = func.call1((py_request,))?;
         let result_str = result.str()?.to_string();
 
         Ok(serde_json::from_str(&result_str).unwrap_or_else(|_| serde_json::json!(result_str)))
     }
 }
+
+/// Normalized request info handed to Python endpoint handlers so they see a
+/// consistent `{method, path, query, body, headers}` shape no matter which
+/// transport (`Content-Type`) the client actually used.
+pub struct EndpointRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Value,
+    pub body: Value,
+    pub headers: Value,
+}
@@ -0,0 +1,131 @@
+//! Explicit route table: maps URL patterns (with optional `:name` dynamic
+//! segments) to a template and/or redirect, plus response metadata
+//! (`status`, `cache_control`) that implicit filesystem routing has no way
+//! to express. `page_handler` consults this before falling back to mapping
+//! `/foo` -> `pages/foo.hrml` directly.
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RouteEntry {
+    pub pattern: String,
+    #[serde(default)]
+    pub template: Option<String>,
+    #[serde(default)]
+    pub redirect: Option<String>,
+    #[serde(default)]
+    pub status: Option<u16>,
+    #[serde(default)]
+    pub cache_control: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct RoutesFile {
+    #[serde(rename = "route", default)]
+    routes: Vec<RouteEntry>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RouteTable {
+    routes: Vec<RouteEntry>,
+}
+
+#[derive(Debug, Clone)]
+pub struct RouteMatch<'a> {
+    pub route: &'a RouteEntry,
+    pub params: HashMap<String, String>,
+}
+
+impl RouteTable {
+    /// Loads `routes.toml` if present, otherwise falls back to any
+    /// `[[route]]` entries embedded in `hrml.toml`. Neither existing (or
+    /// neither declaring any routes) just means "use implicit filesystem
+    /// routing for everything", so that's not an error.
+    pub fn load(project_path: &Path) -> Result<Self, String> {
+        let routes_toml = project_path.join("routes.toml");
+        let source_path = if routes_toml.exists() {
+            routes_toml
+        } else {
+            project_path.join("hrml.toml")
+        };
+
+        if !source_path.exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(&source_path)
+            .map_err(|e| format!("Failed to read {}: {}", source_path.display(), e))?;
+        let parsed: RoutesFile = toml::from_str(&content)
+            .map_err(|e| format!("Failed to parse routes in {}: {}", source_path.display(), e))?;
+
+        Ok(Self { routes: parsed.routes })
+    }
+
+    pub fn routes(&self) -> &[RouteEntry] {
+        &self.routes
+    }
+
+    /// Matches `path` (no leading slash) against each route in declaration
+    /// order, capturing `:segment` placeholders positionally. First match
+    /// wins, same as the route list's own order.
+    pub fn match_path(&self, path: &str) -> Option<RouteMatch> {
+        self.routes.iter().find_map(|route| {
+            match_pattern(&route.pattern, path).map(|params| RouteMatch { route, params })
+        })
+    }
+
+    /// Explicit routes whose `template` doesn't exist under `templates_path`
+    /// - a route that will 500 on every request instead of ever rendering.
+    pub fn dangling(&self, templates_path: &Path) -> Vec<&RouteEntry> {
+        self.routes
+            .iter()
+            .filter(|route| match &route.template {
+                Some(template) => !templates_path.join(template).exists(),
+                None => false,
+            })
+            .collect()
+    }
+
+    /// Page templates under `pages/` that can never be reached because an
+    /// explicit, non-dynamic route claims the same URL and points
+    /// elsewhere (or nowhere, for a redirect/status-only route).
+    pub fn unreachable_templates(&self, pages_path: &Path) -> Vec<String> {
+        self.routes
+            .iter()
+            .filter(|route| !route.pattern.contains(':'))
+            .filter_map(|route| {
+                let relative = route.pattern.trim_matches('/');
+                let implicit_template = format!("pages/{}.hrml", relative);
+                let shadows_own_page = match &route.template {
+                    Some(template) => template != &implicit_template,
+                    None => true,
+                };
+                let own_page_exists = pages_path.join(relative).with_extension("hrml").exists();
+                (shadows_own_page && own_page_exists).then_some(implicit_template)
+            })
+            .collect()
+    }
+}
+
+fn match_pattern(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let pattern_segments: Vec<&str> = pattern.trim_matches('/').split('/').filter(|s| !s.is_empty()).collect();
+    let path_segments: Vec<&str> = path.trim_matches('/').split('/').filter(|s| !s.is_empty()).collect();
+
+    if pattern_segments.len() != path_segments.len() {
+        return None;
+    }
+
+    let mut params = HashMap::new();
+    for (pattern_seg, path_seg) in pattern_segments.iter().zip(path_segments.iter()) {
+        match pattern_seg.strip_prefix(':') {
+            Some(name) => {
+                params.insert(name.to_string(), path_seg.to_string());
+            }
+            None if pattern_seg == path_seg => {}
+            None => return None,
+        }
+    }
+    Some(params)
+}
@@ -0,0 +1,284 @@
+/// Allowlist-based HTML sanitizer for embedding untrusted content (comments,
+/// newsletters, third-party fragments) without XSS. Disallowed elements are
+/// dropped (their text content survives, re-escaped); `on*` event handlers
+/// and `javascript:`/`data:` URLs are stripped; `src` attributes are renamed
+/// to an inert `data-blocked-src` so media can't auto-load. Everything that
+/// does pass through is re-serialized via the crate's normal HTML escaping,
+/// so malformed input can't break out of the surrounding markup.
+use std::collections::{HashMap, HashSet};
+
+use crate::html::escape_html;
+
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Attributes renamed to `data-blocked-<name>` even when the policy allows
+/// them, since they can trigger a network fetch on render.
+const MEDIA_ATTRS: &[&str] = &["src", "poster"];
+
+pub struct Policy {
+    tags: HashSet<String>,
+    attrs: HashMap<String, HashSet<String>>,
+}
+
+impl Policy {
+    pub fn new() -> Self {
+        Self {
+            tags: HashSet::new(),
+            attrs: HashMap::new(),
+        }
+    }
+
+    /// A reasonable default for prose: basic formatting, links, lists,
+    /// quotes, and code — nothing that can execute script or auto-load media.
+    pub fn default_policy() -> Self {
+        let mut policy = Self::new();
+        for tag in [
+            "p", "br", "b", "i", "u", "strong", "em", "a", "ul", "ol", "li", "blockquote", "code",
+            "pre", "span", "div", "h1", "h2", "h3", "h4", "h5", "h6", "img",
+        ] {
+            policy = policy.allow_tag(tag);
+        }
+        policy = policy.allow_attr("a", "href").allow_attr("a", "title");
+        policy = policy.allow_attr("img", "src").allow_attr("img", "alt");
+        policy
+    }
+
+    pub fn allow_tag(mut self, tag: &str) -> Self {
+        self.tags.insert(tag.to_ascii_lowercase());
+        self
+    }
+
+    pub fn allow_attr(mut self, tag: &str, attr: &str) -> Self {
+        self.attrs
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .insert(attr.to_ascii_lowercase());
+        self
+    }
+
+    fn tag_allowed(&self, tag: &str) -> bool {
+        self.tags.contains(tag)
+    }
+
+    fn attr_allowed(&self, tag: &str, attr: &str) -> bool {
+        self.attrs
+            .get(tag)
+            .map(|allowed| allowed.contains(attr))
+            .unwrap_or(false)
+    }
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::default_policy()
+    }
+}
+
+/// Attribute names that can carry a URL - checked against `is_dangerous_url`
+/// regardless of which tag a policy allows them on, not just `a[href]`.
+const URL_ATTRS: &[&str] = &["href", "cite", "action", "formaction", "src", "poster"];
+
+fn is_dangerous_url(value: &str) -> bool {
+    // Browsers ignore ASCII whitespace/control characters inside a URL
+    // scheme, so `java\tscript:`/`java\nscript:` still run as
+    // `javascript:` even though a plain `trim()` (which only strips the
+    // ends) and `escape_attr` (which leaves tabs/newlines alone) both let
+    // them through. Stripping them from anywhere in the value first closes
+    // that gap.
+    let cleaned: String = value.chars().filter(|c| !c.is_ascii_control() && !c.is_whitespace()).collect();
+    let trimmed = cleaned.to_ascii_lowercase();
+    trimmed.starts_with("javascript:") || trimmed.starts_with("data:")
+}
+
+struct Tag {
+    name: String,
+    attrs: Vec<(String, String)>,
+    self_closing: bool,
+}
+
+/// Sanitize `untrusted_html` against `policy`, returning safe, well-formed
+/// markup suitable for `Element::child`/`Element::child_sanitized`.
+pub fn sanitize(untrusted_html: &str, policy: &Policy) -> String {
+    let chars: Vec<char> = untrusted_html.chars().collect();
+    let mut pos = 0;
+    let mut out = String::new();
+    let mut open_stack: Vec<String> = Vec::new();
+
+    while pos < chars.len() {
+        if chars[pos] == '<' {
+            if chars[pos..].iter().collect::<String>().starts_with("<!--") {
+                pos = find(&chars, pos, "-->").map(|i| i + 3).unwrap_or(chars.len());
+                continue;
+            }
+            if chars.get(pos + 1) == Some(&'!') {
+                pos = find(&chars, pos, ">").map(|i| i + 1).unwrap_or(chars.len());
+                continue;
+            }
+            if chars.get(pos + 1) == Some(&'/') {
+                let (name, next) = parse_closing_tag(&chars, pos);
+                pos = next;
+                if let Some(depth) = open_stack.iter().rposition(|t| *t == name) {
+                    if depth == open_stack.len() - 1 && policy.tag_allowed(&name) {
+                        out.push_str(&format!("</{}>", name));
+                        open_stack.pop();
+                    }
+                }
+                continue;
+            }
+            if let Some((tag, next)) = parse_opening_tag(&chars, pos) {
+                pos = next;
+                let is_void = VOID_ELEMENTS.contains(&tag.name.as_str()) || tag.self_closing;
+                if policy.tag_allowed(&tag.name) {
+                    let attrs = render_attrs(&tag, policy);
+                    if is_void {
+                        out.push_str(&format!("<{}{}>", tag.name, attrs));
+                    } else {
+                        out.push_str(&format!("<{}{}>", tag.name, attrs));
+                        open_stack.push(tag.name);
+                    }
+                }
+                continue;
+            }
+            // Malformed `<` with no recognizable tag: treat as literal text.
+            out.push_str(&escape_html("<"));
+            pos += 1;
+            continue;
+        }
+
+        let start = pos;
+        while pos < chars.len() && chars[pos] != '<' {
+            pos += 1;
+        }
+        out.push_str(&escape_html(&chars[start..pos].iter().collect::<String>()));
+    }
+
+    while let Some(tag) = open_stack.pop() {
+        out.push_str(&format!("</{}>", tag));
+    }
+
+    out
+}
+
+fn render_attrs(tag: &Tag, policy: &Policy) -> String {
+    let mut rendered = String::new();
+    for (name, value) in &tag.attrs {
+        let name_lower = name.to_ascii_lowercase();
+        if name_lower.starts_with("on") {
+            continue;
+        }
+        if !policy.attr_allowed(&tag.name, &name_lower) {
+            continue;
+        }
+        if MEDIA_ATTRS.contains(&name_lower.as_str()) {
+            rendered.push_str(&format!(" data-blocked-src=\"{}\"", crate::html::escape_attr(value)));
+            continue;
+        }
+        if URL_ATTRS.contains(&name_lower.as_str()) && is_dangerous_url(value) {
+            continue;
+        }
+        rendered.push_str(&format!(" {}=\"{}\"", name_lower, crate::html::escape_attr(value)));
+    }
+    rendered
+}
+
+fn find(chars: &[char], from: usize, needle: &str) -> Option<usize> {
+    let needle: Vec<char> = needle.chars().collect();
+    (from..chars.len().saturating_sub(needle.len().saturating_sub(1))).find(|&i| {
+        needle.iter().enumerate().all(|(j, c)| chars.get(i + j) == Some(c))
+    })
+}
+
+fn parse_closing_tag(chars: &[char], pos: usize) -> (String, usize) {
+    let mut i = pos + 2;
+    let start = i;
+    while i < chars.len() && chars[i] != '>' {
+        i += 1;
+    }
+    let name: String = chars[start..i].iter().collect::<String>().trim().to_ascii_lowercase();
+    (name, (i + 1).min(chars.len()))
+}
+
+fn parse_opening_tag(chars: &[char], pos: usize) -> Option<(Tag, usize)> {
+    let mut i = pos + 1;
+    let name_start = i;
+    while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '-') {
+        i += 1;
+    }
+    if i == name_start {
+        return None;
+    }
+    let name: String = chars[name_start..i].iter().collect::<String>().to_ascii_lowercase();
+
+    let mut attrs = Vec::new();
+    let mut self_closing = false;
+
+    loop {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i >= chars.len() {
+            break;
+        }
+        if chars[i] == '/' && chars.get(i + 1) == Some(&'>') {
+            self_closing = true;
+            i += 2;
+            break;
+        }
+        if chars[i] == '>' {
+            i += 1;
+            break;
+        }
+
+        let attr_name_start = i;
+        while i < chars.len() && chars[i] != '=' && chars[i] != '>' && !chars[i].is_whitespace() {
+            i += 1;
+        }
+        let attr_name: String = chars[attr_name_start..i].iter().collect();
+        if attr_name.is_empty() {
+            i += 1;
+            continue;
+        }
+
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+
+        let mut attr_value = String::new();
+        if i < chars.len() && chars[i] == '=' {
+            i += 1;
+            while i < chars.len() && chars[i].is_whitespace() {
+                i += 1;
+            }
+            if i < chars.len() && (chars[i] == '"' || chars[i] == '\'') {
+                let quote = chars[i];
+                i += 1;
+                let value_start = i;
+                while i < chars.len() && chars[i] != quote {
+                    i += 1;
+                }
+                attr_value = chars[value_start..i].iter().collect();
+                i = (i + 1).min(chars.len());
+            } else {
+                let value_start = i;
+                while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '>' {
+                    i += 1;
+                }
+                attr_value = chars[value_start..i].iter().collect();
+            }
+        }
+
+        attrs.push((attr_name, attr_value));
+    }
+
+    Some((
+        Tag {
+            name,
+            attrs,
+            self_closing,
+        },
+        i,
+    ))
+}
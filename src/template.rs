@@ -1,13 +1,112 @@
 use serde_json::Value;
 use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::fs;
 use std::path::PathBuf;
 
+/// Output escaping policy for interpolated values. `Html` (the default)
+/// entity-escapes everything `<?get?>` produces; `None` passes values
+/// through verbatim, for engines embedding non-HTML output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escaper {
+    Html,
+    None,
+}
+
+impl Escaper {
+    fn escape(self, input: &str) -> String {
+        match self {
+            Escaper::Html => crate::html::escape_html(input),
+            Escaper::None => input.to_string(),
+        }
+    }
+}
+
+/// Where a stringified `Value::String` is landing, so `Context::stringify`
+/// can escape it for that destination instead of emitting it verbatim.
+/// Unlike `Escaper` (applied once to a tag's whole rendered output),
+/// `OutputFormat` governs individual string leaves inside `stringify`'s
+/// array/object recursion — set it only if the default `PlainText` (which
+/// reproduces today's bare `s.clone()` behavior) isn't enough, since
+/// pairing it with `Escaper::Html` escapes twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    #[default]
+    PlainText,
+    Html,
+    Json,
+    Attribute,
+}
+
+impl OutputFormat {
+    fn escape(self, input: &str) -> String {
+        match self {
+            OutputFormat::PlainText => input.to_string(),
+            OutputFormat::Html => crate::html::escape_html(input),
+            OutputFormat::Attribute => crate::html::escape_html(input)
+                .replace(' ', "&#32;")
+                .replace('\t', "&#9;")
+                .replace('\n', "&#10;")
+                .replace('\r', "&#13;"),
+            OutputFormat::Json => serde_json::to_string(input).unwrap_or_else(|_| "\"\"".to_string()),
+        }
+    }
+}
+
+/// Implemented by `#[derive(Template)]` (see the `hrml_derive` crate) for
+/// the compile-time path: `for`/`if`/`get` are lowered to real Rust control
+/// flow and field access against the deriving struct, instead of being
+/// interpreted against a `serde_json::Value` context on every call. Use
+/// this when a template's data shape is known at compile time; keep
+/// `Engine` for templates rendered against dynamic/untyped data.
+pub trait Template: fmt::Display {
+    fn render(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Fixed-precision/locale-style formatting for `Value::Number`. Not set by
+/// `RenderOptions::default`, in which case `Context::stringify` falls back
+/// to plain `to_string()` so existing output is unaffected.
+#[derive(Debug, Clone)]
+pub struct NumberFormat {
+    pub precision: usize,
+    pub thousands_separator: String,
+    pub decimal_separator: String,
+}
+
+/// Separators used when a compound `Value` (array/object) is stringified
+/// for interpolation via `<?get id="..."?>`. Defaults match what authors
+/// would otherwise have to write by hand: `", "` between elements, `"="`
+/// between an object entry's key and value. `number_format` is unset by
+/// default, preserving plain `to_string()` output for numbers.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    pub separator: String,
+    pub kv_separator: String,
+    pub number_format: Option<NumberFormat>,
+    pub output_format: OutputFormat,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            separator: ", ".to_string(),
+            kv_separator: "=".to_string(),
+            number_format: None,
+            output_format: OutputFormat::PlainText,
+        }
+    }
+}
+
 pub struct Engine {
     base_path: PathBuf,
     site_name: String,
     site_description: Option<String>,
     favicon: Option<String>,
+    escaper: Escaper,
+    render_options: RenderOptions,
+    asset_manifest: crate::assets::AssetManifest,
 }
 
 impl Engine {
@@ -17,6 +116,9 @@ impl Engine {
             site_name: "HRML App".to_string(),
             site_description: None,
             favicon: None,
+            escaper: Escaper::Html,
+            render_options: RenderOptions::default(),
+            asset_manifest: crate::assets::AssetManifest::default(),
         }
     }
 
@@ -35,84 +137,165 @@ impl Engine {
         self
     }
 
-    pub fn render(&self, template_path: &str, data: &Value) -> Result<String, String> {
-        let full_path = self.base_path.join(template_path);
-        
+    /// Select the escaping policy applied to `<?get?>` output (see `Escaper`).
+    pub fn with_escaper(mut self, escaper: Escaper) -> Self {
+        self.escaper = escaper;
+        self
+    }
+
+    /// Select the separators used when `<?get id="..."?>` interpolates an
+    /// array or object (see `RenderOptions`).
+    pub fn with_render_options(mut self, render_options: RenderOptions) -> Self {
+        self.render_options = render_options;
+        self
+    }
+
+    /// Supplies the fingerprinted-asset map `<?asset path="..."?>` rewrites
+    /// against. Without one, `<?asset?>` just passes `path` through under
+    /// `/static/` unfingerprinted.
+    pub fn with_asset_manifest(mut self, asset_manifest: crate::assets::AssetManifest) -> Self {
+        self.asset_manifest = asset_manifest;
+        self
+    }
+
+    pub fn render(&self, template_path: &str, data: &Value) -> Result<String, Diagnostic> {
         let mut context = Context::new(data.clone());
-        
-        // Fully resolve the template tree (handling loads and blocks recursively)
+
+        // Fully resolve the template tree (handling loads, blocks and macro
+        // imports recursively)
         let mut visited = std::collections::HashSet::new();
-        let resolved_nodes = self.resolve_with_tracking(template_path, &mut visited)?;
+        let mut macros = HashMap::new();
+        let resolved_nodes = self.resolve_with_tracking(template_path, &mut visited, &mut macros)?;
 
         // Render the final tree
-        let body = self.render_nodes(&resolved_nodes, &mut context)?;
-        
+        let body = self.render_nodes(&resolved_nodes, &mut context, &macros)?;
+
         Ok(self.wrap_html(&body))
     }
 
-    fn resolve_with_tracking(&self, template_path: &str, visited: &mut std::collections::HashSet<String>) -> Result<Vec<Node>, String> {
-        eprintln!("[RESOLVE] Starting: {} (visited: {:?})", template_path, visited);
+    fn resolve_with_tracking(
+        &self,
+        template_path: &str,
+        visited: &mut std::collections::HashSet<String>,
+        macros: &mut HashMap<String, Macro>,
+    ) -> Result<Vec<Node>, Diagnostic> {
         // Check for circular dependencies
         if visited.contains(template_path) {
-            return Err(format!("Circular template dependency detected: {}", template_path));
+            return Err(Diagnostic::new(
+                format!("circular template dependency: {} is already being loaded", template_path),
+                template_path,
+                String::new(),
+                Span { start: 0, end: 0 },
+            ));
         }
         visited.insert(template_path.to_string());
-        eprintln!("[RESOLVE] Reading file: {}", template_path);
-        
+
         let full_path = self.base_path.join(template_path);
-        let content = fs::read_to_string(&full_path)
-            .map_err(|e| format!("Failed to read template {}: {}", template_path, e))?;
-        eprintln!("[RESOLVE] Read {} bytes from {}", content.len(), template_path);
-            
-        let mut nodes = Parser::new(&content).parse()?;
-        eprintln!("[RESOLVE] Parsed {} nodes from {}", nodes.len(), template_path);
-        
-        // 1. Extract blocks defined in this template
+        let content = fs::read_to_string(&full_path).map_err(|e| {
+            Diagnostic::new(
+                format!("failed to read template: {}", e),
+                template_path,
+                String::new(),
+                Span { start: 0, end: 0 },
+            )
+        })?;
+
+        let mut nodes = Parser::new(template_path, &content).parse()?;
+
+        // 1. Extract blocks and macros defined in this template
         let blocks = self.extract_blocks(&nodes);
-        eprintln!("[RESOLVE] Extracted {} blocks from {}", blocks.len(), template_path);
-        
-        // 2. Remove block Nodes from the tree
-        nodes.retain(|n| !matches!(n, Node::Element { name, .. } if name == "block"));
-        
-        // 3. Process loads recursively
+        for (name, mac) in self.extract_macros(&nodes) {
+            macros.entry(name).or_insert(mac);
+        }
+
+        // 2. Remove block/macro Nodes from the tree (definitions, not output)
+        nodes.retain(|n| !matches!(n, Node::Element { name, .. } if name == "block" || name == "macro"));
+
+        // 3. Process loads and imports recursively
         let mut resolved_nodes = Vec::new();
-        
+
         for node in nodes {
-            if let Node::VoidElement { name, attrs } = &node {
+            if let Node::VoidElement { name, attrs, span } = &node {
                 if name == "load" {
                     if let Some(file) = attrs.get("file") {
-                        eprintln!("[RESOLVE] Found <?load file=\"{}\"?> in {}", file, template_path);
                         // Recursively resolve the loaded file with the SAME visited set
-                        let mut loaded_nodes = self.resolve_with_tracking(file, visited)?;
-                        eprintln!("[RESOLVE] Loaded {} nodes from {}", loaded_nodes.len(), file);
-                        
+                        let mut loaded_nodes = self.resolve_with_tracking(file, visited, macros).map_err(|e| {
+                            e.with_label(
+                                template_path,
+                                content.clone(),
+                                *span,
+                                format!("loaded here via <?load file=\"{}\"?>", file),
+                            )
+                        })?;
+
                         // Apply OUR blocks to the LOADED nodes
-                        eprintln!("[RESOLVE] Injecting blocks into {}", file);
                         loaded_nodes = self.inject_blocks(loaded_nodes, &blocks);
-                        eprintln!("[RESOLVE] Injected blocks into {}", file);
-                        
+
                         resolved_nodes.extend(loaded_nodes);
                         continue;
                     }
+                } else if name == "import" {
+                    if let Some(file) = attrs.get("file") {
+                        // Imports only pull macros into scope; the loaded
+                        // file's own nodes aren't part of our output.
+                        self.resolve_with_tracking(file, visited, macros).map_err(|e| {
+                            e.with_label(
+                                template_path,
+                                content.clone(),
+                                *span,
+                                format!("imported here via <?import file=\"{}\"?>", file),
+                            )
+                        })?;
+                    }
+                    continue;
                 }
             }
             resolved_nodes.push(node);
         }
-        eprintln!("[RESOLVE] Finished resolving {}: {} final nodes", template_path, resolved_nodes.len());
-        
+
         visited.remove(template_path);
         Ok(resolved_nodes)
     }
 
-    fn resolve(&self, template_path: &str) -> Result<Vec<Node>, String> {
+    fn resolve(&self, template_path: &str) -> Result<Vec<Node>, Diagnostic> {
         let mut visited = std::collections::HashSet::new();
-        self.resolve_with_tracking(template_path, &mut visited)
+        let mut macros = HashMap::new();
+        self.resolve_with_tracking(template_path, &mut visited, &mut macros)
+    }
+
+    /// Fully resolve `template_path` (loads, imports, circular-dependency
+    /// checking) without rendering it, for tooling that needs the resolved
+    /// tree shape but not HTML output, e.g. the LSP's diagnostics pass and
+    /// `hrml_derive`'s compile-time codegen.
+    pub fn resolve_for_tooling(&self, template_path: &str) -> Result<Vec<Node>, Diagnostic> {
+        self.resolve(template_path)
+    }
+
+    pub fn base_path(&self) -> &std::path::Path {
+        &self.base_path
+    }
+
+    /// Scan `nodes` for top-level `<?macro?>` definitions (mirrors
+    /// `extract_blocks`'s shallow scan).
+    fn extract_macros(&self, nodes: &[Node]) -> HashMap<String, Macro> {
+        let mut macros = HashMap::new();
+        for node in nodes {
+            if let Node::Element { name, attrs, children, .. } = node {
+                if name == "macro" {
+                    if let Some(macro_name) = attrs.get("name") {
+                        let params = attrs.get("args").map(|args| parse_macro_params(args)).unwrap_or_default();
+                        macros.insert(macro_name.clone(), Macro { params, body: children.clone() });
+                    }
+                }
+            }
+        }
+        macros
     }
 
     fn extract_blocks(&self, nodes: &[Node]) -> HashMap<String, Vec<Node>> {
         let mut blocks = HashMap::new();
         for node in nodes {
-            if let Node::Element { name, attrs, children } = node {
+            if let Node::Element { name, attrs, children, .. } = node {
                 if name == "block" {
                     if let Some(slot) = attrs.get("slot") {
                         blocks.insert(slot.clone(), children.clone());
@@ -125,10 +308,10 @@ impl Engine {
 
     fn inject_blocks(&self, parent_nodes: Vec<Node>, blocks: &HashMap<String, Vec<Node>>) -> Vec<Node> {
         let mut new_nodes = Vec::new();
-        
+
         for node in parent_nodes {
             match node {
-                Node::Element { name, attrs, children } => {
+                Node::Element { name, attrs, children, span } => {
                     if name == "slot" {
                         if let Some(id) = attrs.get("id") {
                             if let Some(block_content) = blocks.get(id) {
@@ -147,135 +330,179 @@ impl Engine {
                             name,
                             attrs,
                             children: self.inject_blocks(children, blocks),
+                            span,
                         });
                     }
                 }
                 _ => new_nodes.push(node),
             }
         }
-        
+
         new_nodes
     }
 
-    fn render_nodes(&self, nodes: &[Node], context: &mut Context) -> Result<String, String> {
+    fn render_nodes(&self, nodes: &[Node], context: &mut Context, macros: &HashMap<String, Macro>) -> Result<String, Diagnostic> {
         let mut result = String::new();
         for node in nodes {
-            result.push_str(&self.render_node(node, context)?);
+            result.push_str(&self.render_node(node, context, macros)?);
         }
         Ok(result)
     }
 
-    fn render_node(&self, node: &Node, context: &mut Context) -> Result<String, String> {
+    fn render_node(&self, node: &Node, context: &mut Context, macros: &HashMap<String, Macro>) -> Result<String, Diagnostic> {
         match node {
-            Node::Text(text) => Ok(text.clone()),
-            Node::VoidElement { name, attrs } => {
+            Node::Text(text, _) => Ok(text.clone()),
+            Node::VoidElement { name, attrs, .. } => {
                 match name.as_str() {
                     "load" => Ok(String::new()),
+                    "import" => Ok(String::new()),
                     "else" => Ok(String::new()),
                     "set" => {
-                        // Void set: <?set key="val" ?>
-                        // We check attrs
-                        for (k, v) in attrs {
-                             // "key" and "value" might be explicit, or just k=v
-                             if k == "id" {
-                                  if let Some(val) = attrs.get("value") {
-                                      context.set(k, val.clone());
-                                  }
-                             } else if k != "value" {
-                                  // Generic <?set var="val"?>
-                                  // This implementation uses strict attrs from parser.
-                                  // But user might use <?set x="y"?> ??
-                                  // Let's assume standard HRML: <?set id="name">val<?/set> or <?set id="name" value="val"?>
-                                  if k == "id" && attrs.contains_key("value") {
-                                      context.set(attrs.get("id").unwrap(), attrs.get("value").unwrap().clone());
-                                  }
-                             }
-                        }
-                        // Alternate interpretation: ANY Attribute is a set? 
-                        // Let's stick to explicit id/value or matching tests.
+                        // Void set: <?set id="name" value="val" ?>; add `raw` to
+                        // store already-safe markup that `get` shouldn't re-escape.
                         if let (Some(id), Some(val)) = (attrs.get("id"), attrs.get("value")) {
-                            context.set(id, val.clone());
+                            context.set_with_safety(id, Value::String(val.clone()), attrs.contains_key("raw"));
                         }
                         Ok(String::new())
                     },
                     "get" => {
-                         if let Some(id) = attrs.get("id") {
-                             Ok(context.get(id))
+                         if let Some(expr_src) = attrs.get("expr") {
+                             let rendered = evaluate_expr(expr_src, context).to_display();
+                             Ok(self.escape_unless_raw(&rendered, attrs.contains_key("raw")))
+                         } else if let Some(id) = attrs.get("id") {
+                             let safe = attrs.contains_key("raw") || context.is_safe(id);
+                             Ok(self.escape_unless_raw(&context.get_with_options(id, &self.render_options), safe))
                          } else {
                              Ok(String::new())
                          }
                     },
-                    _ => Ok(String::new()), 
+                    "asset" => {
+                        match attrs.get("path") {
+                            Some(path) => Ok(self.asset_manifest.resolve(path)),
+                            None => Ok(String::new()),
+                        }
+                    },
+                    _ => Ok(String::new()),
                 }
             },
-            Node::Element { name, attrs, children } => {
+            Node::Element { name, attrs, children, .. } => {
                 match name.as_str() {
-                    "block" => self.render_nodes(children, context),
-                    "slot" => self.render_nodes(children, context),
-                    "if" => self.render_if(attrs, children, context),
-                    "for" => self.render_for(attrs, children, context),
+                    "block" => self.render_nodes(children, context, macros),
+                    "slot" => self.render_nodes(children, context, macros),
+                    "macro" => Ok(String::new()),
+                    "if" => self.render_if(attrs, children, context, macros),
+                    "for" => self.render_for(attrs, children, context, macros),
+                    "call" => self.render_call(attrs, children, context, macros),
                     "set" => {
+                        let raw = attrs.contains_key("raw");
                         if let Some(id) = attrs.get("id") {
-                            let content = self.render_nodes(children, context)?;
-                            context.set(id, content);
-                        } else {
-                            // Support <?set id="x" value="y"?> style as Element too if children empty
-                            if let (Some(id), Some(val)) = (attrs.get("id"), attrs.get("value")) {
-                                context.set(id, val.clone());
+                            if children.is_empty() {
+                                // Support <?set id="x" value="y"?> style as Element too if children empty
+                                if let Some(val) = attrs.get("value") {
+                                    context.set_with_safety(id, Value::String(val.clone()), raw);
+                                }
+                            } else {
+                                // The block form's content is already-rendered
+                                // markup (escaping, if any, already happened
+                                // while rendering `children`), so it must be
+                                // stored safe - otherwise a later `<?get?>`
+                                // escapes it a second time.
+                                let content = self.render_nodes(children, context, macros)?;
+                                context.set_with_safety(id, Value::String(content), true);
                             }
                         }
                         Ok(String::new())
                     },
                     "btn" => {
-                        let inner = self.render_nodes(children, context)?;
+                        let inner = self.render_nodes(children, context, macros)?;
                         let method = if attrs.contains_key("post") { "post" } else { "get" };
                         let endpoint = attrs.get(method).unwrap_or(&String::new()).clone();
                         let target = attrs.get("target").cloned().unwrap_or_else(|| "#body".to_string());
                         let swap = attrs.get("swap").cloned().unwrap_or_else(|| "innerHTML".to_string());
-                        
+
                         Ok(format!(
                             r#"<button class="btn btn-primary" data-{}="{}" data-target="{}" data-swap="{}">{}</button>"#,
                             method, endpoint, target, swap, inner
                         ))
                     },
                     "link" => {
-                        let inner = self.render_nodes(children, context)?;
+                        let inner = self.render_nodes(children, context, macros)?;
                         let endpoint = attrs.get("get").unwrap_or(&String::new()).clone();
                         let target = attrs.get("target").cloned().unwrap_or_else(|| "#body".to_string());
                         let swap = attrs.get("swap").cloned().unwrap_or_else(|| "innerHTML".to_string());
-                        
+
                         Ok(format!(
                             r##"<a href="#" data-get="{}" data-target="{}" data-swap="{}">{}</a>"##,
                             endpoint, target, swap, inner
                         ))
                     },
                     "form" => {
-                        let inner = self.render_nodes(children, context)?;
+                        let inner = self.render_nodes(children, context, macros)?;
                         let endpoint = attrs.get("post").unwrap_or(&String::new()).clone();
                         let target = attrs.get("target").cloned().unwrap_or_else(|| "#body".to_string());
                         let swap = attrs.get("swap").cloned().unwrap_or_else(|| "innerHTML".to_string());
-                        
+
                         Ok(format!(
                             r#"<form data-post="{}" data-target="{}" data-swap="{}">{}</form>"#,
                             endpoint, target, swap, inner
                         ))
                     },
-                    _ => self.render_nodes(children, context),
+                    _ => self.render_nodes(children, context, macros),
                 }
             }
         }
     }
 
-    fn render_if(&self, attrs: &HashMap<String, String>, children: &[Node], context: &mut Context) -> Result<String, String> {
+    /// `<?call macro="card" title="'Hi'"?>...<?/call?>` invokes a `<?macro?>`
+    /// defined (or `<?import?>`ed) elsewhere. Each declared param is
+    /// resolved from the matching attribute (evaluated through the
+    /// expression subsystem, so `title="user.name"` works) or its default,
+    /// then bound in a fresh child `Context` the macro body renders against.
+    /// The call's own children become the macro's `<?slot id="body"?>`.
+    fn render_call(&self, attrs: &HashMap<String, String>, children: &[Node], context: &mut Context, macros: &HashMap<String, Macro>) -> Result<String, Diagnostic> {
+        let Some(name) = attrs.get("macro") else { return Ok(String::new()) };
+        let Some(mac) = macros.get(name).cloned() else { return Ok(String::new()) };
+
+        let mut macro_context = Context::new(context.data.clone());
+        for param in &mac.params {
+            let value = if let Some(raw) = attrs.get(&param.name) {
+                evaluate_expr(raw, context).into_value()
+            } else if let Some(default) = &param.default {
+                evaluate_expr(default, context).into_value()
+            } else {
+                Value::Null
+            };
+            macro_context.set(&param.name, value);
+        }
+
+        let mut body_blocks = HashMap::new();
+        if !children.is_empty() {
+            body_blocks.insert("body".to_string(), children.to_vec());
+        }
+        let body = self.inject_blocks(mac.body, &body_blocks);
+        self.render_nodes(&body, &mut macro_context, macros)
+    }
+
+    /// Apply `self.escaper` to `text`, unless `skip` (a `raw` attribute or an
+    /// already-safe stored var) says it's trusted markup.
+    fn escape_unless_raw(&self, text: &str, skip: bool) -> String {
+        if skip {
+            text.to_string()
+        } else {
+            self.escaper.escape(text)
+        }
+    }
+
+    fn render_if(&self, attrs: &HashMap<String, String>, children: &[Node], context: &mut Context, macros: &HashMap<String, Macro>) -> Result<String, Diagnostic> {
         let condition = attrs.get("cond").cloned().unwrap_or_default();
         let is_true = self.eval_condition(&condition, context);
-        
+
         let (true_nodes, false_nodes) = self.split_if_children(children);
-        
+
         if is_true {
-            self.render_nodes(&true_nodes, context)
+            self.render_nodes(&true_nodes, context, macros)
         } else {
-            self.render_nodes(&false_nodes, context)
+            self.render_nodes(&false_nodes, context, macros)
         }
     }
 
@@ -300,30 +527,62 @@ impl Engine {
         (true_branch, false_branch)
     }
 
-    fn render_for(&self, attrs: &HashMap<String, String>, children: &[Node], context: &Context) -> Result<String, String> {
-        let item_var = attrs.get("in").and_then(|s| s.split_whitespace().next()).unwrap_or("item");
-        // For simplicity, just handling basic list iteration placeholder
-        let items = vec!["item1", "item2", "item3"];
-        
+    /// `in="item items"` binds each element of `items` (a dotted path into
+    /// the context) to `item`; `in="k v map"` binds an object's keys/values
+    /// to `k`/`v`. Each iteration also gets Askama-style `loop.*` metadata.
+    fn render_for(&self, attrs: &HashMap<String, String>, children: &[Node], context: &Context, macros: &HashMap<String, Macro>) -> Result<String, Diagnostic> {
+        let spec = attrs.get("in").cloned().unwrap_or_default();
+        let tokens: Vec<&str> = spec.split_whitespace().collect();
+
+        let (key_var, item_var, path) = match tokens.as_slice() {
+            [item, path] => (None, *item, *path),
+            [key, value, path] => (Some(*key), *value, *path),
+            _ => return Ok(String::new()),
+        };
+
+        let collection = context.get_value(path).unwrap_or(Value::Null);
         let mut output = String::new();
-        for item in items {
-            let mut loop_ctx = context.clone();
-            loop_ctx.set(item_var, item.to_string());
-            output.push_str(&self.render_nodes(children, &mut loop_ctx)?);
+
+        match collection {
+            Value::Array(items) => {
+                let length = items.len();
+                for (index, item) in items.into_iter().enumerate() {
+                    let mut loop_ctx = context.clone();
+                    loop_ctx.set(item_var, item);
+                    Self::set_loop_meta(&mut loop_ctx, index, length);
+                    output.push_str(&self.render_nodes(children, &mut loop_ctx, macros)?);
+                }
+            }
+            Value::Object(map) => {
+                let length = map.len();
+                for (index, (key, value)) in map.into_iter().enumerate() {
+                    let mut loop_ctx = context.clone();
+                    if let Some(key_var) = key_var {
+                        loop_ctx.set(key_var, Value::String(key));
+                        loop_ctx.set(item_var, value);
+                    } else {
+                        loop_ctx.set(item_var, Value::String(key));
+                    }
+                    Self::set_loop_meta(&mut loop_ctx, index, length);
+                    output.push_str(&self.render_nodes(children, &mut loop_ctx, macros)?);
+                }
+            }
+            _ => {}
         }
+
         Ok(output)
     }
 
+    fn set_loop_meta(context: &mut Context, index: usize, length: usize) {
+        context.set("loop.index", Value::from(index + 1));
+        context.set("loop.index0", Value::from(index));
+        context.set("loop.first", Value::from(index == 0));
+        context.set("loop.last", Value::from(index + 1 == length));
+        context.set("loop.length", Value::from(length));
+    }
+
     fn eval_condition(&self, condition: &str, context: &Context) -> bool {
-        if condition.contains("==") {
-            let parts: Vec<&str> = condition.split("==").collect();
-            if parts.len() == 2 {
-                let left = context.get(parts[0].trim());
-                let right = parts[1].trim().trim_matches('"').trim_matches('\'');
-                return left == right;
-            }
-        }
-        !context.get(condition).is_empty()
+        evaluate_expr(condition, context).truthy()
     }
 
     fn wrap_html(&self, body: &str) -> String {
@@ -353,57 +612,238 @@ impl Engine {
     }
 }
 
+// --- Diagnostics ---
+//
+// Errors carry a source span (char offsets into the template that produced
+// them) plus a snapshot of that template's file name and text, so they can
+// render an Ariadne-style caret-underlined snippet instead of a bare message.
+// A `Diagnostic` can carry secondary `labels` pointing into *other* files
+// (e.g. the `<?load?>` tag that pulled in a template which then failed),
+// each with its own file/source/span.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Label {
+    file: String,
+    source: String,
+    span: Span,
+    text: String,
+}
+
+#[derive(Debug)]
+pub struct Diagnostic {
+    message: String,
+    primary: Label,
+    labels: Vec<Label>,
+}
+
+impl Diagnostic {
+    fn new(message: impl Into<String>, file: impl Into<String>, source: impl Into<String>, span: Span) -> Self {
+        Self {
+            message: message.into(),
+            primary: Label {
+                file: file.into(),
+                source: source.into(),
+                span,
+                text: String::new(),
+            },
+            labels: Vec::new(),
+        }
+    }
+
+    /// Attach a secondary annotation, e.g. the `<?load?>` tag responsible
+    /// for pulling in the file the primary error occurred in.
+    fn with_label(mut self, file: impl Into<String>, source: impl Into<String>, span: Span, text: impl Into<String>) -> Self {
+        self.labels.push(Label {
+            file: file.into(),
+            source: source.into(),
+            span,
+            text: text.into(),
+        });
+        self
+    }
+
+    /// The bare error message, for tooling (e.g. the LSP, `hrml_derive`)
+    /// that renders its own snippet instead of `Display`'s Ariadne-style
+    /// report.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// File the primary span points into (the `<?load?>` chain's innermost
+    /// failure, not necessarily the file the user has open).
+    pub fn primary_file(&self) -> &str {
+        &self.primary.file
+    }
+
+    pub fn primary_span(&self) -> Span {
+        self.primary.span
+    }
+}
+
+fn line_col(source: &str, offset: usize) -> (usize, usize) {
+    let mut line = 1;
+    let mut col = 1;
+    for (i, c) in source.chars().enumerate() {
+        if i >= offset {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+            col = 1;
+        } else {
+            col += 1;
+        }
+    }
+    (line, col)
+}
+
+fn render_snippet(label: &Label) -> String {
+    let (line, col) = line_col(&label.source, label.span.start);
+    let line_text = label.source.lines().nth(line.saturating_sub(1)).unwrap_or("");
+    let width = label.span.end.saturating_sub(label.span.start).max(1);
+    let remaining = line_text.chars().count().saturating_sub(col.saturating_sub(1)).max(1);
+    format!(
+        "  --> {}:{}:{}\n   | {}\n   | {}{}",
+        label.file,
+        line,
+        col,
+        line_text,
+        " ".repeat(col.saturating_sub(1)),
+        "^".repeat(width.min(remaining)),
+    )
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        write!(f, "{}", render_snippet(&self.primary))?;
+        for label in &self.labels {
+            writeln!(f)?;
+            writeln!(f, "note: {}", label.text)?;
+            write!(f, "{}", render_snippet(label))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
 // --- AST ---
 
 #[derive(Debug, Clone)]
-enum Node {
-    Text(String),
+pub enum Node {
+    Text(String, Span),
     Element {
         name: String,
         attrs: HashMap<String, String>,
         children: Vec<Node>,
+        span: Span,
     },
     VoidElement {
         name: String,
         attrs: HashMap<String, String>,
+        span: Span,
     },
 }
 
 impl Node {
-    fn is_void(name: &str) -> bool {
-        matches!(name, 
-            "load" | "get" | "else" | "include" |  // HRML tags
+    pub fn is_void(name: &str) -> bool {
+        matches!(name,
+            "load" | "get" | "else" | "include" | "import" | "asset" |  // HRML tags
             "input" | "br" | "hr" | "img" | "meta" | "link" | "area" | "base" | "col" | "embed" | "param" | "source" | "track" | "wbr"  // HTML void elements
         )
     }
 }
 
+// --- Macros ---
+//
+// `<?macro name="card" args="title body='Untitled'"?>...<?/macro?>` defines a
+// reusable snippet, extracted during `resolve_with_tracking` (like blocks)
+// into a name -> Macro map; `<?call macro="card" title="'Hi'"?>...<?/call?>`
+// invokes one, binding each param in a fresh child Context before rendering
+// the macro's body. A call's own children (if any) become its "body" slot,
+// so a macro can declare `<?slot id="body"?>` as a block-style hole.
+
+#[derive(Debug, Clone)]
+struct MacroParam {
+    name: String,
+    default: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+struct Macro {
+    params: Vec<MacroParam>,
+    body: Vec<Node>,
+}
+
+/// `args="title body='Untitled'"`: space-separated names, optionally
+/// `name=default`. Defaults are plain tokens (no spaces), evaluated through
+/// the expression subsystem just like a passed-in argument.
+fn parse_macro_params(args: &str) -> Vec<MacroParam> {
+    args.split_whitespace()
+        .map(|token| match token.split_once('=') {
+            Some((name, default)) => MacroParam {
+                name: name.to_string(),
+                default: Some(default.to_string()),
+            },
+            None => MacroParam {
+                name: token.to_string(),
+                default: None,
+            },
+        })
+        .collect()
+}
+
 // --- Parser ---
 
+/// Parse a single template's source in isolation (no `load`/`import`
+/// resolution), for tooling that works document-by-document, e.g. the LSP
+/// and `hrml_derive`.
+pub fn parse_source(file: &str, content: &str) -> Result<Vec<Node>, Diagnostic> {
+    Parser::new(file, content).parse()
+}
+
 struct Parser {
     chars: Vec<char>,
     pos: usize,
+    file: String,
+    source: String,
 }
 
 impl Parser {
-    fn new(input: &str) -> Self {
+    fn new(file: &str, input: &str) -> Self {
         Self {
             chars: input.chars().collect(),
             pos: 0,
+            file: file.to_string(),
+            source: input.to_string(),
         }
     }
 
-    fn parse(&mut self) -> Result<Vec<Node>, String> {
-        eprintln!("[PARSER] Starting parse, {} chars total", self.chars.len());
+    fn span_from(&self, start: usize) -> Span {
+        Span { start, end: self.pos }
+    }
+
+    fn error(&self, message: impl Into<String>, span: Span) -> Diagnostic {
+        Diagnostic::new(message, self.file.clone(), self.source.clone(), span)
+    }
+
+    fn parse(&mut self) -> Result<Vec<Node>, Diagnostic> {
         let mut nodes = Vec::new();
         let mut iterations = 0;
         while self.pos < self.chars.len() {
             iterations += 1;
-            if iterations % 100 == 0 {
-                eprintln!("[PARSER] Iteration {}, pos={}/{}", iterations, self.pos, self.chars.len());
-            }
             if iterations > 10000 {
-                return Err(format!("Parser infinite loop detected at pos {}", self.pos));
+                return Err(self.error(
+                    "parser made no progress (possible infinite loop)",
+                    Span { start: self.pos, end: self.pos },
+                ));
             }
             if let Some(node) = self.parse_node()? {
                 nodes.push(node);
@@ -411,21 +851,21 @@ impl Parser {
                 break;
             }
         }
-        eprintln!("[PARSER] Finished parsing, {} nodes, {} iterations", nodes.len(), iterations);
         Ok(nodes)
     }
 
-    fn parse_until(&mut self, closing_tag: &str) -> Result<Vec<Node>, String> {
-        eprintln!("[PARSER] Parsing until </?{}?>", closing_tag);
+    fn parse_until(&mut self, closing_tag: &str) -> Result<Vec<Node>, Diagnostic> {
         let mut nodes = Vec::new();
         let mut iterations = 0;
         while self.pos < self.chars.len() {
             iterations += 1;
             if iterations > 10000 {
-                return Err(format!("Parser infinite loop in parse_until('{}') at pos {}", closing_tag, self.pos));
+                return Err(self.error(
+                    format!("parser made no progress while looking for </?{}?> (possible infinite loop)", closing_tag),
+                    Span { start: self.pos, end: self.pos },
+                ));
             }
             if self.is_closing(closing_tag) {
-                eprintln!("[PARSER] Found closing tag for {}", closing_tag);
                 self.consume_closing(closing_tag);
                 return Ok(nodes);
             }
@@ -435,7 +875,6 @@ impl Parser {
                 break;
             }
         }
-        eprintln!("[PARSER] Reached end without finding </?{}?>", closing_tag);
         Ok(nodes)
     }
 
@@ -477,17 +916,20 @@ impl Parser {
         true
     }
 
-    fn parse_node(&mut self) -> Result<Option<Node>, String> {
+    fn parse_node(&mut self) -> Result<Option<Node>, Diagnostic> {
         if self.pos >= self.chars.len() { return Ok(None); }
 
+        let start = self.pos;
         if self.starts_with("<?") && !self.starts_with("</?") {
              return self.parse_element().map(Some);
         } else if self.starts_with("</?") {
              // Unexpected closing, treat as text
-             return Ok(Some(Node::Text(self.consume_text())));
+             let text = self.consume_text();
+             return Ok(Some(Node::Text(text, self.span_from(start))));
         }
 
-        Ok(Some(Node::Text(self.consume_text())))
+        let text = self.consume_text();
+        Ok(Some(Node::Text(text, self.span_from(start))))
     }
 
     fn consume_text(&mut self) -> String {
@@ -509,14 +951,15 @@ impl Parser {
         self.starts_with("</?")  
     }
 
-    fn parse_element(&mut self) -> Result<Node, String> {
+    fn parse_element(&mut self) -> Result<Node, Diagnostic> {
+        let start = self.pos;
         self.pos += 2; // Skip <?
-        
+
         let name = self.consume_identifier();
         let attrs = self.parse_attributes();
-        
+
         while self.pos < self.chars.len() && self.chars[self.pos].is_whitespace() { self.pos += 1; }
-        
+
         // Check for self-closing marker /?> which is sometimes used
         // But mainly rely on node_is_void or closing ?>
         if self.starts_with("?>") {
@@ -530,10 +973,10 @@ impl Parser {
         }
 
         if Node::is_void(&name) {
-            Ok(Node::VoidElement { name, attrs })
+            Ok(Node::VoidElement { name, attrs, span: self.span_from(start) })
         } else {
             let children = self.parse_until(&name)?;
-            Ok(Node::Element { name, attrs, children })
+            Ok(Node::Element { name, attrs, children, span: self.span_from(start) })
         }
     }
 
@@ -600,10 +1043,18 @@ impl Parser {
     }
 }
 
+/// A bound template variable, tagged with whether it's already-safe markup
+/// (set via `raw`) so `get` knows not to re-escape it.
+#[derive(Clone)]
+struct StoredVar {
+    value: Value,
+    safe: bool,
+}
+
 #[derive(Clone)]
 struct Context {
     data: Value,
-    vars: HashMap<String, String>,
+    vars: HashMap<String, StoredVar>,
 }
 
 impl Context {
@@ -614,27 +1065,456 @@ impl Context {
         }
     }
 
-    fn set(&mut self, key: &str, value: String) {
-        self.vars.insert(key.to_string(), value);
+    /// Bind `key` to `value`, defaulting to "not yet escaped" so `get`
+    /// applies the engine's escaper.
+    fn set(&mut self, key: &str, value: Value) {
+        self.set_with_safety(key, value, false);
     }
 
-    fn get(&self, key: &str) -> String {
-        if let Some(val) = self.vars.get(key) {
-            return val.clone();
+    fn set_with_safety(&mut self, key: &str, value: Value, safe: bool) {
+        self.vars.insert(key.to_string(), StoredVar { value, safe });
+    }
+
+    /// Whether `key` names a var explicitly marked safe. Dotted paths into
+    /// the root `data` are never safe — only `Context.vars` can be.
+    fn is_safe(&self, key: &str) -> bool {
+        self.vars.get(key).map(|stored| stored.safe).unwrap_or(false)
+    }
+
+    /// Resolve `key` to its bound `Value`. Checks `vars` for an exact match
+    /// first (so flat keys like `loop.index` and bound loop variables work),
+    /// then walks a dotted path (`item.title`, `user.posts`) against either
+    /// a bound variable or the root `data`.
+    fn get_value(&self, key: &str) -> Option<Value> {
+        if let Some(stored) = self.vars.get(key) {
+            return Some(stored.value.clone());
         }
-        let parts: Vec<&str> = key.split('.').collect();
-        let mut current = &self.data;
+
+        let mut parts = key.split('.');
+        let first = parts.next()?;
+        let mut current = if let Some(stored) = self.vars.get(first) {
+            stored.value.clone()
+        } else {
+            self.data.get(first)?.clone()
+        };
         for part in parts {
-            current = match current.get(part) {
-                Some(v) => v,
-                None => return String::new(),
-            };
+            current = current.get(part)?.clone();
         }
-        match current {
-            Value::String(s) => s.clone(),
-            Value::Number(n) => n.to_string(),
+        Some(current)
+    }
+
+    fn get(&self, key: &str) -> String {
+        self.get_with_options(key, &RenderOptions::default())
+    }
+
+    /// Like `get`, but renders arrays and objects recursively instead of
+    /// yielding an empty string for them (see `RenderOptions`).
+    fn get_with_options(&self, key: &str, options: &RenderOptions) -> String {
+        match self.get_value(key) {
+            Some(value) => Self::stringify(&value, options),
+            None => String::new(),
+        }
+    }
+
+    fn stringify(value: &Value, options: &RenderOptions) -> String {
+        match value {
+            Value::String(s) => options.output_format.escape(s),
+            Value::Number(n) => match &options.number_format {
+                Some(format) => Self::format_number(n, format),
+                None => n.to_string(),
+            },
             Value::Bool(b) => b.to_string(),
-            _ => String::new(),
+            Value::Null => String::new(),
+            Value::Array(items) => items
+                .iter()
+                .map(|item| Self::stringify(item, options))
+                .collect::<Vec<_>>()
+                .join(&options.separator),
+            Value::Object(map) => map
+                .iter()
+                .map(|(k, v)| format!("{}{}{}", k, options.kv_separator, Self::stringify(v, options)))
+                .collect::<Vec<_>>()
+                .join(&options.separator),
+        }
+    }
+
+    /// Fixed-precision rendering with thousands/decimal separators, e.g.
+    /// `1234.5` with precision 2 and a `,` thousands separator -> `1,234.50`.
+    fn format_number(n: &serde_json::Number, format: &NumberFormat) -> String {
+        let value = n.as_f64().unwrap_or(0.0);
+        let magnitude = format!("{:.*}", format.precision, value.abs());
+        let (int_part, frac_part) = magnitude.split_once('.').unwrap_or((magnitude.as_str(), ""));
+        let grouped = Self::group_thousands(int_part, &format.thousands_separator);
+        let sign = if value.is_sign_negative() && value != 0.0 { "-" } else { "" };
+
+        if format.precision == 0 {
+            format!("{}{}", sign, grouped)
+        } else {
+            format!("{}{}{}{}", sign, grouped, format.decimal_separator, frac_part)
+        }
+    }
+
+    fn group_thousands(digits: &str, separator: &str) -> String {
+        let chars: Vec<char> = digits.chars().collect();
+        let mut result = String::new();
+        for (i, c) in chars.iter().enumerate() {
+            if i > 0 && (chars.len() - i) % 3 == 0 {
+                result.push_str(separator);
+            }
+            result.push(*c);
+        }
+        result
+    }
+}
+
+// --- Expression subsystem ---
+//
+// A small Pratt/recursive-descent evaluator for `<?if cond="...">` and
+// `<?get expr="...">`, in precedence order low-to-high: `||`, `&&`,
+// comparison, additive, multiplicative, unary.
+
+#[derive(Debug, Clone, PartialEq)]
+enum RuntimeValue {
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null,
+}
+
+impl RuntimeValue {
+    fn from_value(value: &Value) -> Self {
+        match value {
+            Value::String(s) => RuntimeValue::String(s.clone()),
+            Value::Number(n) => RuntimeValue::Number(n.as_f64().unwrap_or(0.0)),
+            Value::Bool(b) => RuntimeValue::Bool(*b),
+            _ => RuntimeValue::Null,
+        }
+    }
+
+    fn truthy(&self) -> bool {
+        match self {
+            RuntimeValue::String(s) => !s.is_empty(),
+            RuntimeValue::Number(n) => *n != 0.0,
+            RuntimeValue::Bool(b) => *b,
+            RuntimeValue::Null => false,
         }
     }
+
+    /// Numeric coercion for arithmetic/comparison: only `Number` and
+    /// numeric-looking `String`s coerce; `Bool`/`Null` don't.
+    fn as_number(&self) -> Option<f64> {
+        match self {
+            RuntimeValue::Number(n) => Some(*n),
+            RuntimeValue::String(s) => s.trim().parse::<f64>().ok(),
+            _ => None,
+        }
+    }
+
+    fn to_display(&self) -> String {
+        match self {
+            RuntimeValue::String(s) => s.clone(),
+            RuntimeValue::Number(n) => n.to_string(),
+            RuntimeValue::Bool(b) => b.to_string(),
+            RuntimeValue::Null => String::new(),
+        }
+    }
+
+    /// Convert back to a `Value` for binding into a `Context` (e.g. a
+    /// `<?call?>` argument evaluated against the caller's scope).
+    fn into_value(self) -> Value {
+        match self {
+            RuntimeValue::String(s) => Value::String(s),
+            RuntimeValue::Number(n) => serde_json::Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null),
+            RuntimeValue::Bool(b) => Value::Bool(b),
+            RuntimeValue::Null => Value::Null,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Num(f64),
+    Bool(bool),
+    Null,
+    Op(String),
+    LParen,
+    RParen,
+}
+
+fn tokenize_expr(input: &str) -> Vec<Token> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut pos = 0;
+    let mut tokens = Vec::new();
+
+    while pos < chars.len() {
+        let c = chars[pos];
+
+        if c.is_whitespace() {
+            pos += 1;
+            continue;
+        }
+
+        if c == '"' || c == '\'' {
+            let quote = c;
+            pos += 1;
+            let start = pos;
+            while pos < chars.len() && chars[pos] != quote {
+                pos += 1;
+            }
+            tokens.push(Token::Str(chars[start..pos].iter().collect()));
+            if pos < chars.len() {
+                pos += 1;
+            }
+            continue;
+        }
+
+        if c.is_ascii_digit() {
+            let start = pos;
+            while pos < chars.len() && (chars[pos].is_ascii_digit() || chars[pos] == '.') {
+                pos += 1;
+            }
+            let text: String = chars[start..pos].iter().collect();
+            tokens.push(Token::Num(text.parse().unwrap_or(0.0)));
+            continue;
+        }
+
+        if c.is_alphabetic() || c == '_' {
+            let start = pos;
+            while pos < chars.len()
+                && (chars[pos].is_alphanumeric() || chars[pos] == '_' || chars[pos] == '.')
+            {
+                pos += 1;
+            }
+            let word: String = chars[start..pos].iter().collect();
+            tokens.push(match word.as_str() {
+                "true" => Token::Bool(true),
+                "false" => Token::Bool(false),
+                "null" => Token::Null,
+                _ => Token::Ident(word),
+            });
+            continue;
+        }
+
+        if c == '(' {
+            tokens.push(Token::LParen);
+            pos += 1;
+            continue;
+        }
+        if c == ')' {
+            tokens.push(Token::RParen);
+            pos += 1;
+            continue;
+        }
+
+        let two: String = chars[pos..(pos + 2).min(chars.len())].iter().collect();
+        if ["==", "!=", "<=", ">=", "&&", "||"].contains(&two.as_str()) {
+            tokens.push(Token::Op(two));
+            pos += 2;
+            continue;
+        }
+
+        if "<>+-*/!".contains(c) {
+            tokens.push(Token::Op(c.to_string()));
+            pos += 1;
+            continue;
+        }
+
+        // Unrecognized character: skip it rather than fail the whole template.
+        pos += 1;
+    }
+
+    tokens
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Literal(RuntimeValue),
+    Ident(String),
+    Unary(char, Box<Expr>),
+    Binary(Box<Expr>, String, Box<Expr>),
+}
+
+struct ExprParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn new(input: &str) -> Self {
+        Self {
+            tokens: tokenize_expr(input),
+            pos: 0,
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn advance(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn peek_op(&self) -> Option<&str> {
+        match self.peek() {
+            Some(Token::Op(op)) => Some(op.as_str()),
+            _ => None,
+        }
+    }
+
+    fn parse(&mut self) -> Expr {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Expr {
+        let mut left = self.parse_and();
+        while self.peek_op() == Some("||") {
+            self.advance();
+            let right = self.parse_and();
+            left = Expr::Binary(Box::new(left), "||".to_string(), Box::new(right));
+        }
+        left
+    }
+
+    fn parse_and(&mut self) -> Expr {
+        let mut left = self.parse_comparison();
+        while self.peek_op() == Some("&&") {
+            self.advance();
+            let right = self.parse_comparison();
+            left = Expr::Binary(Box::new(left), "&&".to_string(), Box::new(right));
+        }
+        left
+    }
+
+    fn parse_comparison(&mut self) -> Expr {
+        let mut left = self.parse_additive();
+        while matches!(self.peek_op(), Some("==" | "!=" | "<" | "<=" | ">" | ">=")) {
+            let op = self.peek_op().unwrap().to_string();
+            self.advance();
+            let right = self.parse_additive();
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+        left
+    }
+
+    fn parse_additive(&mut self) -> Expr {
+        let mut left = self.parse_multiplicative();
+        while matches!(self.peek_op(), Some("+" | "-")) {
+            let op = self.peek_op().unwrap().to_string();
+            self.advance();
+            let right = self.parse_multiplicative();
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+        left
+    }
+
+    fn parse_multiplicative(&mut self) -> Expr {
+        let mut left = self.parse_unary();
+        while matches!(self.peek_op(), Some("*" | "/")) {
+            let op = self.peek_op().unwrap().to_string();
+            self.advance();
+            let right = self.parse_unary();
+            left = Expr::Binary(Box::new(left), op, Box::new(right));
+        }
+        left
+    }
+
+    fn parse_unary(&mut self) -> Expr {
+        if matches!(self.peek_op(), Some("!" | "-")) {
+            let op = self.peek_op().unwrap().chars().next().unwrap();
+            self.advance();
+            return Expr::Unary(op, Box::new(self.parse_unary()));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Expr {
+        match self.advance() {
+            Some(Token::Num(n)) => Expr::Literal(RuntimeValue::Number(n)),
+            Some(Token::Str(s)) => Expr::Literal(RuntimeValue::String(s)),
+            Some(Token::Bool(b)) => Expr::Literal(RuntimeValue::Bool(b)),
+            Some(Token::Null) => Expr::Literal(RuntimeValue::Null),
+            Some(Token::Ident(name)) => Expr::Ident(name),
+            Some(Token::LParen) => {
+                let inner = self.parse_or();
+                if matches!(self.peek(), Some(Token::RParen)) {
+                    self.advance();
+                }
+                inner
+            }
+            _ => Expr::Literal(RuntimeValue::Null),
+        }
+    }
+}
+
+fn compare(op: &str, left: &RuntimeValue, right: &RuntimeValue) -> bool {
+    if let (Some(l), Some(r)) = (left.as_number(), right.as_number()) {
+        match op {
+            "==" => l == r,
+            "!=" => l != r,
+            "<" => l < r,
+            "<=" => l <= r,
+            ">" => l > r,
+            ">=" => l >= r,
+            _ => false,
+        }
+    } else {
+        let l = left.to_display();
+        let r = right.to_display();
+        match op {
+            "==" => l == r,
+            "!=" => l != r,
+            "<" => l < r,
+            "<=" => l <= r,
+            ">" => l > r,
+            ">=" => l >= r,
+            _ => false,
+        }
+    }
+}
+
+fn eval_expr(expr: &Expr, context: &Context) -> RuntimeValue {
+    match expr {
+        Expr::Literal(value) => value.clone(),
+        Expr::Ident(name) => context
+            .get_value(name)
+            .map(|v| RuntimeValue::from_value(&v))
+            .unwrap_or(RuntimeValue::Null),
+        Expr::Unary(op, inner) => {
+            let value = eval_expr(inner, context);
+            match op {
+                '!' => RuntimeValue::Bool(!value.truthy()),
+                '-' => RuntimeValue::Number(-value.as_number().unwrap_or(0.0)),
+                _ => RuntimeValue::Null,
+            }
+        }
+        Expr::Binary(left, op, right) => match op.as_str() {
+            "||" => RuntimeValue::Bool(eval_expr(left, context).truthy() || eval_expr(right, context).truthy()),
+            "&&" => RuntimeValue::Bool(eval_expr(left, context).truthy() && eval_expr(right, context).truthy()),
+            "==" | "!=" | "<" | "<=" | ">" | ">=" => {
+                RuntimeValue::Bool(compare(op, &eval_expr(left, context), &eval_expr(right, context)))
+            }
+            "+" | "-" | "*" | "/" => {
+                let l = eval_expr(left, context).as_number().unwrap_or(0.0);
+                let r = eval_expr(right, context).as_number().unwrap_or(0.0);
+                RuntimeValue::Number(match op.as_str() {
+                    "+" => l + r,
+                    "-" => l - r,
+                    "*" => l * r,
+                    "/" => l / r,
+                    _ => 0.0,
+                })
+            }
+            _ => RuntimeValue::Null,
+        },
+    }
+}
+
+fn evaluate_expr(source: &str, context: &Context) -> RuntimeValue {
+    let expr = ExprParser::new(source).parse();
+    eval_expr(&expr, context)
 }
@@ -1,4 +1,4 @@
-use hrml::template::Engine;
+use hrml::template::{Engine, NumberFormat, OutputFormat, RenderOptions};
 use serde_json::json;
 use std::fs;
 use std::path::Path;
@@ -186,3 +186,147 @@ fn test_nested_load() {
     assert!(!result.contains("<?slot"), "Unprocessed slot tag");
     assert!(!result.contains("<?block"), "Unprocessed block tag");
 }
+
+#[test]
+fn test_get_renders_arrays_and_nested_objects() {
+    let test_dir = setup_test_templates("compound_values");
+    fs::write(
+        format!("{}/pages/compound_test.hrml", test_dir),
+        r#"<p>Tags: <?get id="tags"?></p>
+<p>Items: <?get id="items"?></p>"#,
+    )
+    .unwrap();
+
+    let engine = Engine::new(&test_dir);
+    let data = json!({
+        "tags": ["rust", "templates", "web"],
+        "items": [
+            {"name": "widget", "qty": 2},
+            {"name": "gadget", "qty": 5}
+        ],
+    });
+    let result = engine.render("pages/compound_test.hrml", &data).unwrap();
+
+    println!("Compound value test result:\n{}", result);
+
+    // Arrays join elements with the default ", " separator instead of
+    // disappearing.
+    assert!(result.contains("Tags: rust, templates, web"), "Array not rendered: {}", result);
+
+    // Nested arrays-of-objects: each object renders as `key=value` pairs,
+    // still joined with ", " at every level.
+    assert!(
+        result.contains("Items: name=widget, qty=2, name=gadget, qty=5"),
+        "Nested array of objects not rendered: {}",
+        result
+    );
+}
+
+#[test]
+fn test_number_format_defaults_to_plain_to_string() {
+    let test_dir = setup_test_templates("number_default");
+    fs::write(
+        format!("{}/pages/number_test.hrml", test_dir),
+        r#"<p><?get id="price"?></p>"#,
+    )
+    .unwrap();
+
+    let engine = Engine::new(&test_dir);
+    let result = engine.render("pages/number_test.hrml", &json!({ "price": 1234.5 })).unwrap();
+
+    assert!(result.contains("<p>1234.5</p>"), "Unset NumberFormat changed output: {}", result);
+}
+
+#[test]
+fn test_number_format_applies_precision_and_separators() {
+    let test_dir = setup_test_templates("number_format");
+    fs::write(
+        format!("{}/pages/number_test.hrml", test_dir),
+        r#"<p><?get id="price"?></p>"#,
+    )
+    .unwrap();
+
+    let engine = Engine::new(&test_dir).with_render_options(RenderOptions {
+        number_format: Some(NumberFormat {
+            precision: 2,
+            thousands_separator: ",".to_string(),
+            decimal_separator: ".".to_string(),
+        }),
+        ..Default::default()
+    });
+    let result = engine.render("pages/number_test.hrml", &json!({ "price": 1234.5 })).unwrap();
+
+    assert!(result.contains("<p>1,234.50</p>"), "Number not formatted: {}", result);
+}
+
+#[test]
+fn test_output_format_plain_text_is_verbatim() {
+    let test_dir = setup_test_templates("format_plain");
+    fs::write(format!("{}/pages/format_test.hrml", test_dir), r#"<?get id="value" raw?>"#).unwrap();
+
+    let engine = Engine::new(&test_dir).with_render_options(RenderOptions {
+        output_format: OutputFormat::PlainText,
+        ..Default::default()
+    });
+    let result = engine
+        .render("pages/format_test.hrml", &json!({ "value": "<b>&\"'</b>" }))
+        .unwrap();
+
+    assert!(result.contains("<b>&\"'</b>"), "PlainText escaped when it shouldn't: {}", result);
+}
+
+#[test]
+fn test_output_format_html_escapes_markup() {
+    let test_dir = setup_test_templates("format_html");
+    fs::write(format!("{}/pages/format_test.hrml", test_dir), r#"<?get id="value" raw?>"#).unwrap();
+
+    let engine = Engine::new(&test_dir).with_render_options(RenderOptions {
+        output_format: OutputFormat::Html,
+        ..Default::default()
+    });
+    let result = engine
+        .render("pages/format_test.hrml", &json!({ "value": "<script>&\"</script>" }))
+        .unwrap();
+
+    assert!(!result.contains("<script>"), "Html format left markup unescaped: {}", result);
+    assert!(result.contains("&lt;script&gt;"), "Html format missing entity escape: {}", result);
+}
+
+#[test]
+fn test_output_format_attribute_escapes_whitespace() {
+    let test_dir = setup_test_templates("format_attribute");
+    fs::write(format!("{}/pages/format_test.hrml", test_dir), r#"<?get id="value" raw?>"#).unwrap();
+
+    let engine = Engine::new(&test_dir).with_render_options(RenderOptions {
+        output_format: OutputFormat::Attribute,
+        ..Default::default()
+    });
+    let result = engine
+        .render("pages/format_test.hrml", &json!({ "value": "a b\tc\nd" }))
+        .unwrap();
+
+    assert!(!result.contains(' ') || result.contains("&#32;"), "Attribute format left a breaking space: {}", result);
+    assert!(result.contains("&#32;"), "Attribute format missing space escape: {}", result);
+    assert!(result.contains("&#9;"), "Attribute format missing tab escape: {}", result);
+    assert!(result.contains("&#10;"), "Attribute format missing newline escape: {}", result);
+}
+
+#[test]
+fn test_output_format_json_quotes_and_escapes() {
+    let test_dir = setup_test_templates("format_json");
+    fs::write(format!("{}/pages/format_test.hrml", test_dir), r#"<?get id="value" raw?>"#).unwrap();
+
+    let engine = Engine::new(&test_dir).with_render_options(RenderOptions {
+        output_format: OutputFormat::Json,
+        ..Default::default()
+    });
+    let result = engine
+        .render("pages/format_test.hrml", &json!({ "value": "She said \"hi\"\n" }))
+        .unwrap();
+
+    assert!(
+        result.contains(r#""She said \"hi\"\n""#),
+        "Value not emitted as a quoted/escaped JSON string: {}",
+        result
+    );
+}